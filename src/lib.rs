@@ -49,9 +49,23 @@
 //!
 //! - retrieving VCs
 //! - validating VCs, which will
-//!   - check `proof` (if attached)
-//!   - check `credentialStatus` online (if attached)
+//!   - check `proof`, VCs without `proof` are rejected unless allowed by `policy`
+//!   - check that `proof.verificationMethod` is a key of the VC's `issuer` or one of its `controller`s, listed in `assertionMethod` (`authentication` if missing), whatever `proofPurpose` the proof claims
+//!   - keys in DID documents may be given as `ethereumAddress`, `publicKeyHex`, `publicKeyJwk` or `publicKeyBase58`
+//!   - proofs may be `EcdsaPublicKeySecp256k1` (default), `Ed25519Signature2018` (detached JWS with EdDSA) or `JsonWebSignature2020` (detached JWS with ES256K or EdDSA), select with `proof_type` in `CreateVcOptions`
+//!   - `EcdsaPublicKeySecp256k1` proofs may be signed with `ES256K-R` (default) or `ES256K` (select with `jws_alg` in `CreateVcOptions`), `ES256K` signatures are verified with the key from the issuer's DID
+//!   - documents in JWT payloads and detached JWS are compared in their canonical form (JCS, RFC 8785), RDF dataset normalization is not supported
+//!   - VCs may be created and checked as compact JWT-VCs (`vc-jwt`), select with `as_jwt` in `CreateVcOptions`
+//!   - check `validFrom`, `validUntil` and `expirationDate` (if attached)
+//!   - check `credentialStatus` online (if attached), optionally cached with a `status_cache`
+//!   - obey a `policy` with required checks, allowed proof types, trusted issuers per credential type (`TrustedIssuerRegistry`), allowed status types and offline checks with cached statuses and DIDs
+//! - getting detailed reports of performed checks with `verify_vc_detailed`
+//! - validating many VCs at once with `check_vcs`, DIDs and statuses are resolved only once per batch
 //! - creating VCs
+//! - storing VCs (requires a `vc_storage`)
+//! - creating and validating VPs with `create_vp_with_signer` and `check_vp`, bound to a verifier's `challenge` and `domain`
+//! - rejecting replayed challenges of VPs and proofs with a `nonce_store` (`InMemoryNonceStore`, `FileNonceStore`)
+//! - creating VCs and VPs with keys held by a `Signer` (`InMemorySigner`, `KeystoreSigner` for Ethereum keystore files) or an `Ed25519Signer` (`InMemoryEd25519Signer`) instead of raw private keys
 //!
 //! #### Retrieving VCs
 //!
@@ -131,17 +145,19 @@
 //!   - a DID document for the issuer of our VC
 //!   - a 64B private key as `str`, used to create the `proof` property (of course not IN the DID document ;)) 
 //!   - a way to identify this key, as the `ethereumAddress` of it is IN the DID document
-//! - an `id` for the VC - as the VCs created with `vade-evan` are currently not stored onchain, we cannot rely on automatic ID generation (`id` can currently be anything, but you should try to avoid reusing IDs to avoid overriding your documents locally)
+//! - an `id` for the VC - if the resolver has a `vc_storage` that creates ids (e.g. [`InMemoryVcStorage`]), a new `id` is created automatically, otherwise it has to be provided (the VC registry on [evan.network] used by [`EvanVcStorage`] does not create ids) (`id` can be anything then, but you should try to avoid reusing IDs to avoid overriding your documents locally)
 //!
 //! As an example take this test function:
 //!
 //! ```rust
 //! use serde_json::Value;
 //! use vade_evan::plugin::rust_vcresolver_evan::{
+//!     CreateVcOptions,
 //!     RustVcResolverEvan,
 //!     VC_DEFAULT_TYPE,
 //!     VC_W3C_MANDATORY_CONTEXT,
 //! };
+//! use vade_evan::utils::signer::InMemorySigner;
 //!
 //! async fn vc_resolver_can_create_new_vcs() -> std::result::Result<(), Box<dyn std::error::Error>> {
 //!     // issuer of the new VC
@@ -162,7 +178,8 @@
 //!
 //!     let vcr = RustVcResolverEvan::new();
 //!
-//!     let vc: String = vcr.create_vc(partial_vc_data, &veri_method, &veri_pkey).await.unwrap();
+//!     let signer = InMemorySigner::from_hex(veri_method, veri_pkey)?;
+//!     let vc: String = vcr.create_vc_with_signer(partial_vc_data, &signer, &CreateVcOptions::new()).await?;
 //!
 //!     let parsed: Value = serde_json::from_str(&vc).unwrap();
 //!
@@ -192,6 +209,9 @@
 //! Allows to work with DIDs on [evan.network], currently includes:
 //!
 //! - retrieving DIDs
+//! - validating DIDs
+//! - setting DIDs (requires a signing key listed in the DID's or its controller's `authentication` and a write endpoint)
+//! - caching DIDs (optional, requires a `did_cache`)
 //!
 //! #### Retrieving DIDs
 //!
@@ -210,11 +230,43 @@
 //! # }
 //! ```
 //!
+//! ### Nodes and Transports
+//!
+//! Both resolvers query the nodes configured per network in a [`ResolverConfig`] and send their requests through a [`Transport`], [`ReqwestTransport`] is used by default. Custom transports can be used to add headers, proxies or custom root certificates, [`FixtureTransport`] answers requests from registered responses without network access. Ids without network segment, e.g. `did:evan:0x...`, are resolved on `testcore`, use `with_default_network("core")` to resolve them on mainnet. Documents are written to agents added with `with_write_endpoint`, e.g.:
+//!
+//! ```rust
+//! use vade_evan::plugin::rust_didresolver_evan::RustDidResolverEvan;
+//! use vade_evan::utils::config::ResolverConfig;
+//! use vade_evan::utils::transport::{ FixtureTransport, ReqwestTransport };
+//!
+//! // send an auth header to a custom node
+//! let config = ResolverConfig::new().with_endpoint("testcore", "https://my-node.example.org/");
+//! let transport = ReqwestTransport::new().with_header("Authorization", "Bearer 1234");
+//! let rde = RustDidResolverEvan::new_with_transport(config, Box::new(transport));
+//!
+//! // resolve ids without network segment on mainnet
+//! let rde = RustDidResolverEvan::new_with_config(ResolverConfig::new().with_default_network("core"));
+//!
+//! // write DID documents to an agent
+//! let rde = RustDidResolverEvan::new_with_config(ResolverConfig::new().with_write_endpoint("testcore", "https://my-agent.example.org/"));
+//!
+//! // answer requests from fixtures
+//! let transport = FixtureTransport::new()
+//!     .with_response("GET", "https://testcore.evan.network/did/did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f", r#"{"status":"success","did":{}}"#);
+//! let rde = RustDidResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(transport));
+//! ```
+//!
 //! [`DidResolver`]: https://docs.rs/vade/*/vade/traits/trait.DidResolver.html
+//! [`EvanVcStorage`]: https://docs.rs/vade-evan/*/vade_evan/utils/vc_storage/struct.EvanVcStorage.html
+//! [`FixtureTransport`]: https://docs.rs/vade-evan/*/vade_evan/utils/transport/struct.FixtureTransport.html
 //! [`get_vc_document`]: https://docs.rs/vade/*/vade/traits/trait.VcResolver.html#tymethod.get_vc_document
+//! [`InMemoryVcStorage`]: https://docs.rs/vade-evan/*/vade_evan/utils/vc_storage/struct.InMemoryVcStorage.html
+//! [`ResolverConfig`]: https://docs.rs/vade-evan/*/vade_evan/utils/config/struct.ResolverConfig.html
+//! [`ReqwestTransport`]: https://docs.rs/vade-evan/*/vade_evan/utils/transport/struct.ReqwestTransport.html
 //! [`RustDidResolver`]: https://docs.rs/vade-evan/*/vade_evan/plugin/rust_didresolver_evan/struct.RustDidResolverEvan.html
 //! [`RustVcResolver`]: https://docs.rs/vade-evan/*/vade_evan/plugin/rust_vcresolver_evan/struct.RustVcResolverEvan.html
 //! [`serde_json`]: https://docs.rs/serde_json/*/serde_json
+//! [`Transport`]: https://docs.rs/vade-evan/*/vade_evan/utils/transport/trait.Transport.html
 //! [`vade`]: https://docs.rs/vade
 //! [`Vade`]: https://docs.rs/vade/*/vade/struct.Vade.html
 //! [API documentation]: https://docs.rs/vade-evan
@@ -231,3 +283,4 @@ extern crate vade;
 
 pub mod platform;
 pub mod plugin;
+pub mod utils;
@@ -22,14 +22,18 @@
 //!
 //! - retrieving VCs
 //! - validating VCs, which will
-//!   - check `proof` (if attached)
+//!   - check `proof`, VCs without `proof` are rejected unless allowed by `policy`
 //!   - check `credentialStatus` online (if attached)
+//! - creating VCs
+//! - storing VCs (requires a `vc_storage`)
 //!
 //! ### DID Resolver
 //!
 //! Allows to work with DIDs on [evan.network](https://evan.network/), currently includes:
 //!
 //! - retrieving DIDs
+//! - validating DIDs
+//! - setting DIDs (requires a signing key)
 //!
 //! [`vade`]: https://docs.rs/vade
 
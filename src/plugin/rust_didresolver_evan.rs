@@ -14,66 +14,398 @@
   limitations under the License.
 */
 
-use reqwest;
 use async_trait::async_trait;
+use chrono::{ DateTime, Utc };
+use regex::Regex;
 use vade::traits::{ DidResolver };
 use serde_json::Value;
-use simple_error::SimpleError;
+use crate::utils::cache::LruCache;
+use crate::utils::config::ResolverConfig;
+use crate::utils::errors::VadeEvanError;
+use crate::utils::signer::Signer;
+use crate::utils::signing::{ create_proof, JWS_ALG_ES256K_R };
+use crate::utils::transport::{ ReqwestTransport, Transport };
+use crate::utils::verification_key::VerificationKey;
 
-/// Resolver for DIDs on evan.network (currently on testnet)
+/// context, that has to be included in DID documents
+pub const DID_W3C_MANDATORY_CONTEXT: &str = "https://w3id.org/did/v1";
+const DID_REGEX: &str = r"^did:[a-z0-9]+:[^#]+$";
+const DID_URL_REGEX: &str = r"^(did:[a-z0-9]+:[^#]+)?#.+$";
+const ETHEREUM_ADDRESS_REGEX: &str = r"^0x[0-9a-fA-F]{40}$";
+const PUBLIC_KEY_HEX_REGEX: &str = r"^(0x)?([0-9a-fA-F]{66}|[0-9a-fA-F]{130})$";
+
+/// Resolver for DIDs on evan.network, nodes to query are configured per network in `config`
 pub struct RustDidResolverEvan {
+    pub config: ResolverConfig,
+    /// signer used to sign DID documents when setting them, its key id is used as
+    /// `verificationMethod`, setting documents fails without it
+    pub signer: Option<Box<dyn Signer>>,
+    /// transport used to send requests to nodes
+    pub transport: Box<dyn Transport>,
+    /// cache for fetched DID documents, documents are always fetched from nodes if omitted
+    pub did_cache: Option<LruCache<String>>,
 }
 
 impl RustDidResolverEvan {
-    /// Creates new instance of `RustDidResolverEvan`.
+    /// Creates new instance of `RustDidResolverEvan` with default endpoints for `testcore` and `core`.
     pub fn new() -> RustDidResolverEvan {
-        RustDidResolverEvan { }
+        RustDidResolverEvan::new_with_config(ResolverConfig::new())
+    }
+
+    /// Creates new instance of `RustDidResolverEvan` with custom endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - config with endpoints to use per network
+    pub fn new_with_config(config: ResolverConfig) -> RustDidResolverEvan {
+        RustDidResolverEvan::new_with_transport(config, Box::new(ReqwestTransport::new()))
+    }
+
+    /// Creates new instance of `RustDidResolverEvan` with custom endpoints and transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - config with endpoints to use per network
+    /// * `transport` - transport to send requests with
+    pub fn new_with_transport(config: ResolverConfig, transport: Box<dyn Transport>) -> RustDidResolverEvan {
+        RustDidResolverEvan {
+            config,
+            signer: None,
+            transport,
+            did_cache: None,
+        }
     }
-}
 
-#[async_trait(?Send)]
-impl DidResolver for RustDidResolverEvan {
     /// Checks given DID document.
     /// A DID document is considered as valid if returning ().
     /// Resolver may throw to indicate
     /// - that it is not responsible for this DID
     /// - that it considers this DID as invalid
-    /// 
-    /// Currently the test `did_name` `"test"` is accepted as valid.
+    ///
+    /// Checks performed on the document are
+    /// - `id` has to match `did_name`
+    /// - `@context` has to include the W3C DID context
+    /// - `controller` entries have to be DIDs
+    /// - `publicKey` entries need an `id`, a `type` and a valid `ethereumAddress`, `publicKeyHex`,
+    ///   `publicKeyJwk` or `publicKeyBase58`
+    /// - `authentication` entries have to reference keys from `publicKey` or be valid keys themselves
     ///
     /// # Arguments
     ///
     /// * `did_name` - did_name to check document for
     /// * `value` - value to check
-    async fn check_did(&self, _did_name: &str, _value: &str) -> Result<(), Box<dyn std::error::Error>> {
-        unimplemented!();
+    pub async fn check_did(&self, did_name: &str, value: &str) -> Result<(), VadeEvanError> {
+        if !did_name.starts_with("did:evan:") {
+            return Err(VadeEvanError::Unsupported(format!("not responsible for DID \"{}\"", did_name)));
+        }
+        let document: Value = serde_json::from_str(value)?;
+        check_did_document(did_name, &document)?;
+        debug!("did document of \"{}\" is valid", did_name);
+        Ok(())
     }
 
-    /// Gets document for given did name.
+    /// Gets document for given did name, from `did_cache` if set and the document has been fetched before.
     ///
     /// # Arguments
     ///
     /// * `did_name` - did_name to fetch
-    async fn get_did_document(&self, did_id: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let body = reqwest::get(&format!("https://testcore.evan.network/did/{}", did_id))
-            .await?
-            .text()
-            .await?;
-        let parsed: Value = serde_json::from_str(&body).unwrap();
+    pub async fn get_did_document(&self, did_id: &str) -> Result<String, VadeEvanError> {
+        if let Some(did_document) = self.did_cache.as_ref().and_then(|cache| cache.get(did_id)) {
+            debug!("using cached did document for {}", did_id);
+            return Ok(did_document);
+        }
+        let url = self.config.get_url("did", did_id)?;
+        let body = self.transport.get(&url).await?;
+        let parsed: Value = serde_json::from_str(&body)?;
         if parsed["status"] == "error" {
-            Err(Box::new(SimpleError::new(format!("could not get did document, {:?}", parsed["error"].as_str().unwrap()))))
-        } else {
-            Ok(serde_json::to_string(&parsed["did"]).unwrap())
+            return Err(VadeEvanError::Network(format!("could not get did document, {:?}", parsed["error"].as_str().unwrap_or_default())));
+        }
+        let did_document = serde_json::to_string(&parsed["did"])?;
+        if let Some(cache) = &self.did_cache {
+            cache.set(did_id, did_document.to_string());
         }
+        Ok(did_document)
     }
 
     /// Sets document for given did name.
     ///
+    /// The document is checked with [`check_did`], signed with `signer`
+    /// and then sent to the write endpoint configured for the DID's network. The key of `signer`
+    /// has to be listed in the document's `authentication` or in the `authentication` of one of
+    /// its controllers. Existing `proof` properties are replaced. Cached documents for this DID
+    /// are dropped.
+    ///
     /// # Arguments
     ///
     /// * `did_name` - did_name to set value for
     /// * `value` - value to set
-    async fn set_did_document(&mut self, _did_id: &str, _value: &str) -> std::result::Result<(), Box<dyn std::error::Error>> {
-        unimplemented!();
+    ///
+    /// [`check_did`]: RustDidResolverEvan#method.check_did
+    pub async fn set_did_document(&mut self, did_id: &str, value: &str) -> Result<(), VadeEvanError> {
+        self.check_did(did_id, value).await?;
+        let signer = match &self.signer {
+            Some(signer) => signer,
+            None => return Err(VadeEvanError::Config("no signer set, cannot sign did document".to_string())),
+        };
+
+        let key_id = signer.key_id();
+        let mut document: Value = serde_json::from_str(value)?;
+        self.check_authentication_key(did_id, &document, &key_id).await?;
+
+        // sign document without proof
+        if let Some(document) = document.as_object_mut() {
+            document.remove("proof");
+        }
+        let issuer = key_id.split('#').next().unwrap_or_default();
+        let now: DateTime<Utc> = Utc::now();
+        document["proof"] = create_proof(
+            &document,
+            "didDocument",
+            issuer,
+            signer.as_ref(),
+            JWS_ALG_ES256K_R,
+            None,
+            &now,
+        ).await?;
+
+        debug!("setting did document for {}", did_id);
+        let url = self.config.get_write_url("did", did_id)?;
+        let mut payload: Value = serde_json::from_str("{}")?;
+        payload["did"] = document;
+        let body = self.transport.post(&url, &payload.to_string()).await?;
+        if let Some(cache) = &self.did_cache {
+            cache.invalidate(did_id);
+        }
+        let parsed: Value = serde_json::from_str(&body)?;
+        if parsed["status"] == "error" {
+            Err(VadeEvanError::Network(format!("could not set did document, {:?}", parsed["error"].as_str().unwrap_or_default())))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks if given key is listed in the `authentication` of a DID document or in the
+    /// `authentication` of one of its controllers, which are fetched with [`get_did_document`].
+    ///
+    /// # Arguments
+    ///
+    /// * `did_name` - DID the document belongs to
+    /// * `document` - parsed DID document
+    /// * `key_id` - absolute id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    ///
+    /// [`get_did_document`]: RustDidResolverEvan#method.get_did_document
+    async fn check_authentication_key(&self, did_name: &str, document: &Value, key_id: &str) -> Result<(), VadeEvanError> {
+        if lists_authentication_key(did_name, document, key_id) {
+            return Ok(());
+        }
+        let controllers = match &document["controller"] {
+            Value::String(controller) => vec![controller.as_str()],
+            Value::Array(controllers) => controllers.iter().filter_map(|c| c.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        for controller in controllers.into_iter().filter(|c| *c != did_name) {
+            let controller_document: Value = serde_json::from_str(&self.get_did_document(controller).await?)?;
+            if lists_authentication_key(controller, &controller_document, key_id) {
+                return Ok(());
+            }
+        }
+        Err(VadeEvanError::KeyNotFound(format!("key {} is not authorized for authentication by did {}", key_id, did_name)))
+    }
+}
+
+impl Default for RustDidResolverEvan {
+    fn default() -> RustDidResolverEvan {
+        RustDidResolverEvan::new()
+    }
+}
+
+#[async_trait(?Send)]
+impl DidResolver for RustDidResolverEvan {
+    /// Checks given DID document, see [`RustDidResolverEvan::check_did`].
+    ///
+    /// # Arguments
+    ///
+    /// * `did_name` - did_name to check document for
+    /// * `value` - value to check
+    async fn check_did(&self, did_name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(RustDidResolverEvan::check_did(self, did_name, value).await?)
+    }
+
+    /// Gets document for given did name, see [`RustDidResolverEvan::get_did_document`].
+    ///
+    /// # Arguments
+    ///
+    /// * `did_name` - did_name to fetch
+    async fn get_did_document(&self, did_id: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(RustDidResolverEvan::get_did_document(self, did_id).await?)
+    }
+
+    /// Sets document for given did name, see [`RustDidResolverEvan::set_did_document`].
+    ///
+    /// # Arguments
+    ///
+    /// * `did_name` - did_name to set value for
+    /// * `value` - value to set
+    async fn set_did_document(&mut self, did_id: &str, value: &str) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        Ok(RustDidResolverEvan::set_did_document(self, did_id, value).await?)
+    }
+}
+
+/// Checks structure of a DID document, see [`check_did`] for checks performed.
+///
+/// # Arguments
+///
+/// * `did_name` - DID the document should belong to
+/// * `document` - parsed DID document
+///
+/// [`check_did`]: RustDidResolverEvan#method.check_did
+fn check_did_document(did_name: &str, document: &Value) -> Result<(), VadeEvanError> {
+    if !document.is_object() {
+        return Err(VadeEvanError::Schema("DID document is not an object".to_string()));
+    }
+
+    if document["id"].as_str() != Some(did_name) {
+        return Err(VadeEvanError::Schema(format!("DID document id {} does not match DID \"{}\"", &document["id"], did_name)));
+    }
+
+    let has_context = match &document["@context"] {
+        Value::String(context) => context == DID_W3C_MANDATORY_CONTEXT,
+        Value::Array(contexts) => contexts.iter().any(|v| v == DID_W3C_MANDATORY_CONTEXT),
+        _ => false,
+    };
+    if !has_context {
+        return Err(VadeEvanError::Schema(format!("\"@context\" has to include \"{}\"", DID_W3C_MANDATORY_CONTEXT)));
+    }
+
+    check_controller(&document["controller"], "controller")?;
+
+    let public_keys = match document["publicKey"].as_array() {
+        Some(public_keys) => public_keys,
+        None => return Err(VadeEvanError::Schema("\"publicKey\" has to be an array".to_string())),
+    };
+    let mut key_ids: Vec<String> = Vec::new();
+    for public_key in public_keys {
+        let key_id = check_public_key(did_name, public_key)?;
+        if key_ids.contains(&key_id) {
+            return Err(VadeEvanError::Schema(format!("duplicate key id \"{}\" in \"publicKey\"", key_id)));
+        }
+        key_ids.push(key_id);
+    }
+
+    match &document["authentication"] {
+        Value::Null => (),
+        Value::Array(entries) => {
+            for entry in entries {
+                match entry {
+                    Value::String(reference) => {
+                        let reference = get_absolute_key_id(did_name, reference);
+                        if !key_ids.contains(&reference) {
+                            return Err(VadeEvanError::Schema(format!("\"authentication\" references unknown key \"{}\"", reference)));
+                        }
+                    },
+                    Value::Object(_) => {
+                        check_public_key(did_name, entry)?;
+                    },
+                    _ => return Err(VadeEvanError::Schema("\"authentication\" entries have to be key references or keys".to_string())),
+                }
+            }
+        },
+        _ => return Err(VadeEvanError::Schema("\"authentication\" has to be an array".to_string())),
+    };
+
+    Ok(())
+}
+
+/// Checks if given key is listed in the `authentication` of a DID document, either as reference
+/// or as embedded key.
+///
+/// # Arguments
+///
+/// * `did_name` - DID the document belongs to, used to resolve relative key ids
+/// * `document` - parsed DID document
+/// * `key_id` - absolute id of key
+fn lists_authentication_key(did_name: &str, document: &Value, key_id: &str) -> bool {
+    match &document["authentication"] {
+        Value::Array(entries) => entries.iter().any(|entry| {
+            let reference = match entry {
+                Value::String(reference) => Some(reference.as_str()),
+                entry => entry["id"].as_str(),
+            };
+            reference.is_some_and(|reference| get_absolute_key_id(did_name, reference) == key_id)
+        }),
+        _ => false,
+    }
+}
+
+/// Checks a `controller` property, that may be missing, a single DID or a list of DIDs.
+///
+/// # Arguments
+///
+/// * `controller` - controller property value
+/// * `property` - name of property for error messages
+fn check_controller(controller: &Value, property: &str) -> Result<(), VadeEvanError> {
+    let did_regex = Regex::new(DID_REGEX)?;
+    let is_valid = match controller {
+        Value::Null => true,
+        Value::String(did) => did_regex.is_match(did),
+        Value::Array(dids) => dids.iter().all(|did| did.as_str().is_some_and(|did| did_regex.is_match(did))),
+        _ => false,
+    };
+    if is_valid {
+        Ok(())
+    } else {
+        Err(VadeEvanError::Schema(format!("\"{}\" has to be a DID or a list of DIDs", property)))
+    }
+}
+
+/// Checks a public key entry and returns its absolute id.
+///
+/// # Arguments
+///
+/// * `did_name` - DID the key belongs to, used to resolve relative key ids
+/// * `public_key` - public key entry from DID document
+fn check_public_key(did_name: &str, public_key: &Value) -> Result<String, VadeEvanError> {
+    let key_id = match public_key["id"].as_str() {
+        Some(key_id) if Regex::new(DID_URL_REGEX)?.is_match(key_id) => get_absolute_key_id(did_name, key_id),
+        _ => return Err(VadeEvanError::Schema(format!("key id {} is not a valid DID URL", &public_key["id"]))),
+    };
+    match public_key["type"].as_str() {
+        Some(key_type) if !key_type.is_empty() => (),
+        _ => return Err(VadeEvanError::Schema(format!("key \"{}\" has no \"type\"", key_id))),
+    };
+    check_controller(&public_key["controller"], &format!("{}.controller", key_id))?;
+
+    let ethereum_address = &public_key["ethereumAddress"];
+    let public_key_hex = &public_key["publicKeyHex"];
+    if ethereum_address.is_null() && public_key_hex.is_null() {
+        // keys given as JWK or base58 are checked by parsing them
+        VerificationKey::parse(public_key).map_err(|e| VadeEvanError::Schema(e.to_string()))?;
+        return Ok(key_id);
+    }
+    let ethereum_address_regex = Regex::new(ETHEREUM_ADDRESS_REGEX)?;
+    if !ethereum_address.is_null()
+            && !ethereum_address.as_str().is_some_and(|v| ethereum_address_regex.is_match(v)) {
+        return Err(VadeEvanError::Schema(format!("key \"{}\" has an invalid \"ethereumAddress\"", key_id)));
+    }
+    let public_key_hex_regex = Regex::new(PUBLIC_KEY_HEX_REGEX)?;
+    if !public_key_hex.is_null()
+            && !public_key_hex.as_str().is_some_and(|v| public_key_hex_regex.is_match(v)) {
+        return Err(VadeEvanError::Schema(format!("key \"{}\" has an invalid \"publicKeyHex\"", key_id)));
+    }
+
+    Ok(key_id)
+}
+
+/// Resolves relative key ids like `"#key-1"` against given DID.
+///
+/// # Arguments
+///
+/// * `did_name` - DID to resolve key id against
+/// * `key_id` - relative or absolute key id
+pub fn get_absolute_key_id(did_name: &str, key_id: &str) -> String {
+    if key_id.starts_with('#') {
+        format!("{}{}", did_name, key_id)
+    } else {
+        key_id.to_string()
     }
 }
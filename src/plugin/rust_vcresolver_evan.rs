@@ -15,20 +15,50 @@
 */
 
 use async_trait::async_trait;
-use chrono::{ DateTime, Utc };
-use data_encoding::BASE64URL;
-use regex::Regex;
-use reqwest;
-use secp256k1::{Message, Signature, recover, RecoveryId, SecretKey, sign};
+use chrono::{ DateTime, Duration, SecondsFormat, TimeZone, Utc };
+use futures::stream::{ self, StreamExt };
 use serde_json::Value;
-use serde::{Serialize, Deserialize};
-use sha2::{Digest, Sha256};
-use sha3::Keccak256;
-use simple_error::SimpleError;
-use std::convert::TryInto;
+use std::cell::RefCell;
+use std::collections::{ HashMap, HashSet, VecDeque };
 use std::str;
 use vade::traits::VcResolver;
 use vade::Vade;
+use crate::plugin::rust_didresolver_evan::get_absolute_key_id;
+use crate::utils::clock::{ Clock, SystemClock };
+use crate::utils::cache::LruCache;
+use crate::utils::config::ResolverConfig;
+use crate::utils::detached_jws::{
+    create_ed25519_proof,
+    create_json_web_signature_proof,
+    verify_detached_jws_proof,
+    JWS_ALG_EDDSA,
+    PROOF_TYPE_ED25519_SIGNATURE_2018,
+    PROOF_TYPE_JSON_WEB_SIGNATURE_2020,
+};
+use crate::utils::errors::VadeEvanError;
+use crate::utils::nonce_store::NonceStore;
+use crate::utils::signer::{ Ed25519Signer, InMemoryEd25519Signer, InMemorySigner, Signer };
+use crate::utils::status_cache::{ StatusCache, StatusCacheMode };
+use crate::utils::signing::{
+    canonicalize_json,
+    create_jwt,
+    create_proof,
+    decode_jwt,
+    get_jwt_algorithm,
+    recover_address_and_data,
+    verify_jwt,
+    JWS_ALG_ES256K,
+    JWS_ALG_ES256K_R,
+};
+use crate::utils::transport::{ ReqwestTransport, Transport };
+use crate::utils::vc_storage::VcStorage;
+use crate::utils::verification_key::VerificationKey;
+use crate::utils::verification_policy::{
+    PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1,
+    STATUS_TYPE_EVAN_CREDENTIAL,
+    VerificationPolicy,
+};
+use crate::utils::verification_report::{ CheckStatus, CheckType, KeyAuthorization, VerificationReport };
 
 /// mandatory context, will be inserted automatically if not provided for
 /// [create_vc](crate::plugin::rust_rust_vcresolver_evan::RustVcResolverEvan#method.create_vc)
@@ -36,110 +66,897 @@ pub const VC_W3C_MANDATORY_CONTEXT: &'static str = "https://www.w3.org/2018/cred
 /// default type, will be used if no type is provided for
 /// [create_vc](crate::plugin::rust_rust_vcresolver_evan::RustVcResolverEvan#method.create_vc)
 pub const VC_DEFAULT_TYPE: &'static str = "VerifiableCredential";
-const JWT_REGEX: &'static str = r#"^\s*\{"iat":[^,]+,"vc":(.*),"iss":"[^"]+?"\}\s*$"#;
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Debug)]
-/// type to cover required fields in evan.network DID documents for key handling,
-/// does NOT reflect a full evan.network DID document
-struct EvanDid {
-    pub publicKey: Vec<EvanDidPublicKey>,
-}
+/// default for `clock_skew` in seconds
+pub const DEFAULT_CLOCK_SKEW_SECONDS: i64 = 300;
+/// default for `batch_parallelism`, number of concurrent lookups in [`check_vcs`](RustVcResolverEvan::check_vcs)
+pub const DEFAULT_BATCH_PARALLELISM: usize = 10;
+/// maximum number of `controller` links followed from the issuer's DID when looking up keys
+pub const MAX_CONTROLLER_DEPTH: usize = 5;
+/// default type, will be used for presentations created with
+/// [create_vp](crate::plugin::rust_vcresolver_evan::RustVcResolverEvan#method.create_vp)
+pub const VP_DEFAULT_TYPE: &str = "VerifiablePresentation";
+/// `proofPurpose` of credential proofs, assumed for proofs without one
+const VC_PROOF_PURPOSE: &str = "assertionMethod";
+/// `proofPurpose` of presentation proofs
+const VP_PROOF_PURPOSE: &str = "authentication";
+/// verification relationships a DID document may declare
+const VERIFICATION_RELATIONSHIPS: [&str; 5] = [
+    "authentication",
+    "assertionMethod",
+    "keyAgreement",
+    "capabilityInvocation",
+    "capabilityDelegation",
+];
 
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Debug)]
-/// type to cover required fields in evan.network DID documents for key handling,
-/// does NOT reflect a full evan.network DID document
-struct EvanDidPublicKey {
-    pub ethereumAddress: String,
-    pub id: String,
+/// DID documents and statuses fetched in advance by [`RustVcResolverEvan::check_vcs`], DID
+/// documents resolved while checking, e.g. of controllers, are added to `did_documents`
+struct Prefetched {
+    did_documents: RefCell<HashMap<String, Result<String, VadeEvanError>>>,
+    statuses: HashMap<String, (Result<bool, VadeEvanError>, Vec<String>)>,
 }
 
-/// Resolver for DIDs on evan.network (currently on testnet)
+/// Resolver for VCs on evan.network, nodes to query are configured per network in `config`
 pub struct RustVcResolverEvan {
+    pub config: ResolverConfig,
     pub vade: Option<Box<Vade>>,
+    /// backend to store VCs in and to create ids for new VCs, VCs are fetched from
+    /// evan.network nodes and cannot be set if omitted
+    pub vc_storage: Option<Box<dyn VcStorage>>,
+    /// transport used to send requests to nodes
+    pub transport: Box<dyn Transport>,
+    /// cache for `credentialStatus` lookups, statuses are always fetched if omitted
+    pub status_cache: Option<StatusCache>,
+    /// cache for DID documents resolved with `vade`, DIDs are always resolved if omitted; the only
+    /// source of DID documents with `offline` set in `policy`
+    pub did_cache: Option<LruCache<String>>,
+    /// store for used `challenge`s of proofs, proofs with a `challenge` are rejected if it has
+    /// been used before; challenges are not checked for replays if omitted
+    pub nonce_store: Option<Box<dyn NonceStore>>,
+    /// maximum number of concurrent DID and status lookups in [`check_vcs`](RustVcResolverEvan::check_vcs)
+    pub batch_parallelism: usize,
+    /// time source for creating VCs and checking their validity period
+    pub clock: Box<dyn Clock>,
+    /// tolerated difference between `clock` and the clock of VC issuers when checking validity periods
+    pub clock_skew: Duration,
+    /// configures which VCs are accepted when checking them
+    pub policy: VerificationPolicy,
+}
+
+/// Options for [`create_vc_with_options`](RustVcResolverEvan::create_vc_with_options).
+#[derive(Clone, Debug)]
+pub struct CreateVcOptions {
+    /// sets `validUntil` to `validFrom` plus this duration if given
+    pub valid_for: Option<Duration>,
+    /// type of proof to create, `EcdsaPublicKeySecp256k1` (default), `Ed25519Signature2018` or
+    /// `JsonWebSignature2020` (detached `ES256K` JWS)
+    pub proof_type: String,
+    /// algorithm of `jws` in `EcdsaPublicKeySecp256k1` proofs, `ES256K-R` (default) or `ES256K`;
+    /// signers of `ES256K` proofs cannot be recovered and are verified with the key from their DID
+    pub jws_alg: String,
+    /// return VC as compact JWT-VC (`vc-jwt`) signed with `jws_alg` instead of a JSON document
+    /// with `proof`, requires `EcdsaPublicKeySecp256k1` as `proof_type`
+    pub as_jwt: bool,
+    /// challenge of a verifier to add to `proof`, covered by its signature; cannot be added to
+    /// JWT-VCs
+    pub challenge: Option<String>,
+}
+
+impl CreateVcOptions {
+    /// Creates new instance of `CreateVcOptions` with default options, used by
+    /// [`create_vc`](RustVcResolverEvan::create_vc).
+    pub fn new() -> CreateVcOptions {
+        CreateVcOptions {
+            valid_for: None,
+            proof_type: PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1.to_string(),
+            jws_alg: JWS_ALG_ES256K_R.to_string(),
+            as_jwt: false,
+            challenge: None,
+        }
+    }
+}
+
+impl Default for CreateVcOptions {
+    fn default() -> CreateVcOptions {
+        CreateVcOptions::new()
+    }
 }
 
 impl RustVcResolverEvan {
-    /// Creates new instance of `RustVcResolverEvan`.
+    /// Creates new instance of `RustVcResolverEvan` with default endpoints for `testcore` and `core`.
     pub fn new() -> RustVcResolverEvan {
+        RustVcResolverEvan::new_with_config(ResolverConfig::new())
+    }
+
+    /// Creates new instance of `RustVcResolverEvan` with custom endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - config with endpoints to use per network
+    pub fn new_with_config(config: ResolverConfig) -> RustVcResolverEvan {
+        RustVcResolverEvan::new_with_transport(config, Box::new(ReqwestTransport::new()))
+    }
+
+    /// Creates new instance of `RustVcResolverEvan` with custom endpoints and transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - config with endpoints to use per network
+    /// * `transport` - transport to send requests with
+    pub fn new_with_transport(config: ResolverConfig, transport: Box<dyn Transport>) -> RustVcResolverEvan {
         match env_logger::try_init() {
             Ok(_) | Err(_) => (),
         };
         RustVcResolverEvan {
+            config,
             vade: None,
+            vc_storage: None,
+            transport,
+            status_cache: None,
+            did_cache: None,
+            nonce_store: None,
+            batch_parallelism: DEFAULT_BATCH_PARALLELISM,
+            clock: Box::new(SystemClock),
+            clock_skew: Duration::seconds(DEFAULT_CLOCK_SKEW_SECONDS),
+            policy: VerificationPolicy::new(),
         }
     }
 
-    /// Sets document for given vc name.
+    /// Checks given Vc document.
+    /// A Vc document is considered as valid if returning ().
+    /// Resolver may throw to indicate
+    /// - that it is not responsible for this Vc
+    /// - that it considers this Vc as invalid
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - vc_id to check document for
+    /// * `value` - value to check
+    pub async fn check_vc(&self, vc_id: &str, value: &str) -> Result<(), VadeEvanError> {
+        for warning in self.check_vc_with_warnings(vc_id, value).await? {
+            warn!("{}", warning);
+        }
+        Ok(())
+    }
+
+    /// Checks given Vc document like [`check_vc`] and returns warnings for checks that passed
+    /// with degraded guarantees, e.g. statuses taken from `status_cache` because the status
+    /// endpoint was unreachable.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - vc_id to check document for
+    /// * `value` - value to check
+    ///
+    /// [`check_vc`]: RustVcResolverEvan#method.check_vc
+    pub async fn check_vc_with_warnings(&self, vc_id: &str, value: &str) -> Result<Vec<String>, VadeEvanError> {
+        self.verify_vc_detailed(vc_id, value).await.into_result()
+    }
+
+    /// Checks given Vc document and returns a report listing all performed checks and their
+    /// outcome. Checking stops at the first failed check.
+    ///
+    /// `value` may be a JSON document or a compact JWT-VC, which is checked like a document with an
+    /// `EcdsaPublicKeySecp256k1` proof, using the key from its `kid` header as `verificationMethod`.
+    /// `iss`, `jti`, `sub`, `nbf` and `exp` claims are added to its `vc` claim as `issuer`, `id`,
+    /// `credentialSubject.id`, `validFrom` and `validUntil` if missing there and have to match them
+    /// otherwise.
+    ///
+    /// Checks performed on the document are
+    /// - [`Schema`]: document is an object, `proof` has a `type`, a `jws` and a `verificationMethod`
+    /// - [`Expiry`]: `validFrom`, `validUntil` and `expirationDate` (if given) include current time
+    ///   of `clock`, tolerating differences up to `clock_skew`
+    /// - [`TrustedIssuer`]: issuer is trusted for one of the VC's types in `trusted_issuers` of
+    ///   `policy`, if configured
+    /// - [`SignatureRecovery`]: proof type is allowed by `policy`, signer address can be recovered from `jws`
+    /// - [`DocumentEquality`]: document in `jws` equals given document, proof options signed in `jws`
+    ///   equal given proof; a `challenge` of proofs without signed proof options is rejected
+    /// - [`KeyLookup`]: `verificationMethod` belongs to `issuer`, is found in issuer's DID and matches
+    ///   signer address
+    /// - [`SignatureVerification`]: only for proofs without recoverable signer like
+    ///   `Ed25519Signature2018` or `ES256K` signed `EcdsaPublicKeySecp256k1` proofs, replaces
+    ///   [`SignatureRecovery`]; proof type is allowed by `policy`, `jws` is a valid signature of the
+    ///   document for the key from [`KeyLookup`], followed by [`DocumentEquality`] for proofs that
+    ///   embed the document
+    /// - [`Challenge`]: only for proofs with a `challenge`, challenge has not been used before
+    ///   according to `nonce_store` and is marked as used, proof has been `created` within the TTL
+    ///   of `nonce_store` minus `clock_skew`; skipped without `nonce_store`
+    /// - [`CredentialStatus`]: status type is allowed by `policy`, VC is active if it has an
+    ///   `evan:evanCredential` status; with `offline` only the last known status from
+    ///   `status_cache` is used
+    ///
+    /// VCs without a `proof` fail at [`SignatureRecovery`], unless they are allowed by `policy`.
+    /// In this case only [`Schema`], [`Expiry`] and [`TrustedIssuer`] are checked.
+    ///
+    /// Checks listed in `required_checks` of `policy` fail if they have been skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - vc_id to check document for
+    /// * `value` - value to check
+    ///
+    /// [`Schema`]: crate::utils::verification_report::CheckType::Schema
+    /// [`Expiry`]: crate::utils::verification_report::CheckType::Expiry
+    /// [`TrustedIssuer`]: crate::utils::verification_report::CheckType::TrustedIssuer
+    /// [`SignatureRecovery`]: crate::utils::verification_report::CheckType::SignatureRecovery
+    /// [`DocumentEquality`]: crate::utils::verification_report::CheckType::DocumentEquality
+    /// [`KeyLookup`]: crate::utils::verification_report::CheckType::KeyLookup
+    /// [`SignatureVerification`]: crate::utils::verification_report::CheckType::SignatureVerification
+    /// [`Challenge`]: crate::utils::verification_report::CheckType::Challenge
+    /// [`CredentialStatus`]: crate::utils::verification_report::CheckType::CredentialStatus
+    pub async fn verify_vc_detailed(&self, vc_id: &str, value: &str) -> VerificationReport {
+        self.verify_vc_prefetched(vc_id, value, None).await
+    }
+
+    /// Checks multiple VC documents like [`check_vc`], DID documents of issuers and their keys and
+    /// statuses needed for this are fetched in advance, each of them only once and up to
+    /// `batch_parallelism` at a time. Statuses are fetched for all VCs with a `credentialStatus`,
+    /// even if their proof is invalid. DID documents of controllers are resolved while checking,
+    /// each of them only once for all VCs.
+    ///
+    /// Returns one result per VC, in the same order as `vcs`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vcs` - tuples of vc_id and value to check
+    ///
+    /// [`check_vc`]: RustVcResolverEvan#method.check_vc
+    pub async fn check_vcs(&self, vcs: &[(&str, &str)]) -> Vec<Result<(), VadeEvanError>> {
+        let mut dids = HashSet::new();
+        let mut status_ids = HashSet::new();
+        for (_, value) in vcs {
+            if let Ok((vc, vc_proof)) = split_proof(value) {
+                if let Some(did) = vc_proof.as_ref().and_then(|proof| proof.verification_method.split('#').next()) {
+                    dids.insert(did.to_string());
+                }
+                if let (Some(_), Ok(issuer)) = (&vc_proof, get_issuer_id(&vc)) {
+                    dids.insert(issuer);
+                }
+                if !self.policy.offline && vc["credentialStatus"]["type"] == STATUS_TYPE_EVAN_CREDENTIAL {
+                    if let Some(status_id) = vc["credentialStatus"]["id"].as_str() {
+                        status_ids.insert(status_id.to_string());
+                    }
+                }
+            }
+        }
+        debug!("prefetching {} DIDs and {} statuses for {} vcs", dids.len(), status_ids.len(), vcs.len());
+
+        let parallelism = self.batch_parallelism.max(1);
+        let did_documents = stream::iter(dids)
+            .map(|did| async move {
+                let did_document = self.resolve_did(&did).await;
+                (did, did_document)
+            })
+            .buffer_unordered(parallelism)
+            .collect();
+        let statuses = stream::iter(status_ids)
+            .map(|status_id| async move {
+                let mut warnings = Vec::new();
+                let status = self.get_vc_status(&status_id, &mut warnings).await;
+                (status_id, (status, warnings))
+            })
+            .buffer_unordered(parallelism)
+            .collect();
+        let (did_documents, statuses) = futures::join!(did_documents, statuses);
+        let prefetched = Prefetched { did_documents: RefCell::new(did_documents), statuses };
+
+        let mut results = Vec::new();
+        for (vc_id, value) in vcs {
+            let result = self.verify_vc_prefetched(vc_id, value, Some(&prefetched)).await
+                .into_result()
+                .map(|warnings| for warning in warnings {
+                    warn!("{}", warning);
+                });
+            results.push(result);
+        }
+        results
+    }
+
+    /// Checks given Vc document, DID documents and statuses are taken from `prefetched` if found there.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - vc_id to check document for
+    /// * `value` - value to check
+    /// * `prefetched` - DID documents and statuses fetched in advance
+    async fn verify_vc_prefetched(
+        &self,
+        vc_id: &str,
+        value: &str,
+        prefetched: Option<&Prefetched>,
+    ) -> VerificationReport {
+        let mut report = self.run_checks(vc_id, value, prefetched).await;
+        self.policy.enforce_required_checks(&mut report);
+        report
+    }
+
+    /// Runs checks for given Vc document as configured in `policy`, required checks are not enforced.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - vc_id to check document for
+    /// * `value` - value to check
+    /// * `prefetched` - DID documents and statuses fetched in advance
+    async fn run_checks(
+        &self,
+        vc_id: &str,
+        value: &str,
+        prefetched: Option<&Prefetched>,
+    ) -> VerificationReport {
+        let mut report = VerificationReport::new(vc_id);
+        // adds result of a check to report, stops checking if it failed
+        macro_rules! check {
+            ($check:expr, $result:expr) => {
+                match $result {
+                    Ok(value) => value,
+                    Err(e) => {
+                        report.add($check, CheckStatus::Failed(e));
+                        return report;
+                    },
+                }
+            };
+        }
+
+        // TODO: add some pre-flight checks (key type, etc)
+        let (vc_without_proof, vc_proof) = check!(CheckType::Schema, split_proof(value));
+        report.add(CheckType::Schema, CheckStatus::Passed);
+        let checked = check!(CheckType::Expiry, check_validity_period(&vc_without_proof, &self.clock.now(), &self.clock_skew));
+        match checked {
+            true => report.add(CheckType::Expiry, CheckStatus::Passed),
+            false => report.add(CheckType::Expiry, CheckStatus::Skipped("vc has no validity period".to_string())),
+        };
+        match self.policy.trusted_issuers {
+            Some(_) => {
+                let issuer = check!(CheckType::TrustedIssuer, get_issuer_id(&vc_without_proof));
+                let credential_types = check!(CheckType::TrustedIssuer, get_credential_types(&vc_without_proof));
+                check!(CheckType::TrustedIssuer, self.policy.check_issuer(&issuer, &credential_types));
+                report.add(CheckType::TrustedIssuer, CheckStatus::Passed);
+            },
+            None => report.add(CheckType::TrustedIssuer, CheckStatus::Skipped("no trusted issuers configured".to_string())),
+        };
+        let vc_proof = match vc_proof {
+            Some(vc_proof) => vc_proof,
+            None if self.policy.allow_unsigned => {
+                debug!("vcs without a proof are allowed by policy");
+                report.add(CheckType::SignatureRecovery, CheckStatus::Skipped("vc has no proof".to_string()));
+                return report;
+            },
+            None => check!(CheckType::SignatureRecovery, Err(VadeEvanError::Signature(
+                "vc has no proof, vcs without proof are not allowed by policy".to_string()))),
+        };
+        debug!("checking vc document");
+        // signer of EcdsaPublicKeySecp256k1 proofs is recovered unless signed with ES256K, other
+        // proofs are verified with the key from DID
+        let recovers_signer = vc_proof.proof_type == PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1 &&
+            get_jwt_algorithm(&vc_proof.jws).ok().as_deref() != Some(JWS_ALG_ES256K);
+        let signature_check = match recovers_signer {
+            true => CheckType::SignatureRecovery,
+            false => CheckType::SignatureVerification,
+        };
+        check!(signature_check, self.policy.check_proof_type(&vc_proof.proof_type));
+        check!(signature_check, get_jwt_algorithm(&vc_proof.jws).and_then(|alg| check_jws_algorithm(&alg)));
+
+        let mut recovered_address = None;
+        if recovers_signer {
+            // recover address and payload text (pure jwt format)
+            let (address, decoded_payload_text) = check!(CheckType::SignatureRecovery, recover_address_and_data(&vc_proof.jws));
+            report.add(CheckType::SignatureRecovery, CheckStatus::Passed);
+
+            debug!("checking if document given and document from jws are equal");
+            check!(CheckType::DocumentEquality, check_document_equality(&vc_without_proof, &decoded_payload_text));
+            check!(CheckType::DocumentEquality, check_proof_options(&vc_proof.proof, &decoded_payload_text));
+            report.add(CheckType::DocumentEquality, CheckStatus::Passed);
+            debug!("recovered address: 0x{}", &address);
+            recovered_address = Some(format!("0x{}", address));
+        }
+
+        debug!("checking proof of vc document");
+        debug!("key to use for verification: {}", &vc_proof.verification_method);
+        let issuer = check!(CheckType::KeyLookup, get_issuer_id(&vc_without_proof));
+        check!(CheckType::KeyLookup, check_proof_purpose(&vc_proof.proof_purpose));
+        // `proofPurpose` is not covered by all signatures, so keys are always looked up for assertions
+        let (key_from_did, key_authorization) = check!(CheckType::KeyLookup, self.get_key_from_did(
+            &issuer, &vc_proof.verification_method, VC_PROOF_PURPOSE, prefetched).await);
+        debug!("key from did: {:?}", &key_from_did);
+        if let Some(address) = recovered_address {
+            if address != check!(CheckType::KeyLookup, key_from_did.ethereum_address()) {
+                check!(CheckType::KeyLookup, Err(VadeEvanError::Signature(format!("could not verify signature of \"{}\"", vc_id))));
+            }
+        }
+        debug!("key authorized via {:?}", &key_authorization);
+        report.key_authorization = Some(key_authorization);
+        report.add(CheckType::KeyLookup, CheckStatus::Passed);
+
+        if !recovers_signer {
+            let decoded_payload_text = check!(
+                CheckType::SignatureVerification, verify_proof_signature(&vc_without_proof, &vc_proof, &key_from_did));
+            report.add(CheckType::SignatureVerification, CheckStatus::Passed);
+            if let Some(decoded_payload_text) = decoded_payload_text {
+                debug!("checking if document given and document from jws are equal");
+                check!(CheckType::DocumentEquality, check_document_equality(&vc_without_proof, &decoded_payload_text));
+                check!(CheckType::DocumentEquality, check_proof_options(&vc_proof.proof, &decoded_payload_text));
+                report.add(CheckType::DocumentEquality, CheckStatus::Passed);
+            }
+        }
+
+        if let Some(challenge) = vc_proof.proof.get("challenge") {
+            debug!("checking if challenge of proof has been used before");
+            let challenge = check!(CheckType::Challenge, challenge.as_str()
+                .ok_or_else(|| VadeEvanError::Schema("\"challenge\" of proof has to be a string".to_string())));
+            match &self.nonce_store {
+                Some(_) => {
+                    let created = check!(CheckType::Challenge, parse_date(&vc_proof.proof, "created")
+                        .and_then(|created| created.ok_or_else(|| VadeEvanError::Schema(
+                            "proof with \"challenge\" has no \"created\"".to_string()))));
+                    check!(CheckType::Challenge, self.use_challenge(challenge, &created));
+                    report.add(CheckType::Challenge, CheckStatus::Passed);
+                },
+                None => report.add(CheckType::Challenge, CheckStatus::Skipped("no nonce store configured".to_string())),
+            }
+        }
+
+        debug!("checking if credential status is present, query it");
+        let credential_status = &vc_without_proof["credentialStatus"];
+        if credential_status.is_null() {
+            report.add(CheckType::CredentialStatus, CheckStatus::Skipped("vc has no credentialStatus".to_string()));
+            return report;
+        }
+        let status_type = check!(CheckType::CredentialStatus, credential_status["type"].as_str()
+            .ok_or_else(|| VadeEvanError::Schema("credentialStatus has no \"type\"".to_string())));
+        check!(CheckType::CredentialStatus, self.policy.check_status_type(status_type));
+        if status_type != STATUS_TYPE_EVAN_CREDENTIAL {
+            report.add(CheckType::CredentialStatus, CheckStatus::Skipped(format!("unsupported credentialStatus type \"{}\"", status_type)));
+        } else {
+            debug!("credential status is present, query it");
+            let status_id = check!(CheckType::CredentialStatus, credential_status["id"].as_str()
+                .ok_or_else(|| VadeEvanError::Schema("credentialStatus has no \"id\"".to_string())));
+            let mut warnings = Vec::new();
+            let vc_status = check!(CheckType::CredentialStatus, match prefetched.and_then(|prefetched| prefetched.statuses.get(status_id)) {
+                Some((status, status_warnings)) => {
+                    warnings.extend(status_warnings.iter().cloned());
+                    status.clone()
+                },
+                None if self.policy.offline => match self.status_cache.as_ref().and_then(|status_cache| status_cache.get_last_known(status_id, &self.clock.now())) {
+                    Some(cached) => {
+                        warnings.push(format!(
+                            "offline, using status from {}", cached.fetched.to_rfc3339_opts(SecondsFormat::Millis, true)));
+                        Ok(cached.active)
+                    },
+                    None => {
+                        report.add(CheckType::CredentialStatus, CheckStatus::Skipped("offline, status is not known".to_string()));
+                        return report;
+                    },
+                },
+                None => self.get_vc_status(status_id, &mut warnings).await,
+            });
+            if !vc_status {
+                check!(CheckType::CredentialStatus, Err(VadeEvanError::Revoked(format!("vc \"{}\" is not active", &vc_id))));
+            }
+            match warnings.len() {
+                0 => report.add(CheckType::CredentialStatus, CheckStatus::Passed),
+                _ => report.add(CheckType::CredentialStatus, CheckStatus::Warning(warnings.join(", "))),
+            };
+        }
+
+        debug!("vc document is valid");
+        report
+    }
+
+    /// Gets document for given vc name from `vc_storage` if set, otherwise from evan.network.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - vc_id to fetch
+    pub async fn get_vc_document(&self, vc_id: &str) -> Result<String, VadeEvanError> {
+        if let Some(vc_storage) = &self.vc_storage {
+            return vc_storage.get(vc_id).await;
+        }
+        let url = self.config.get_url("vc", vc_id)?;
+        let body = self.transport.get(&url).await?;
+        let parsed: Value = serde_json::from_str(&body)?;
+        if parsed["status"] == "error" {
+            Err(VadeEvanError::Network(format!("could not get vc document, {:?}", parsed["error"].as_str().unwrap_or_default())))
+        } else {
+            Ok(serde_json::to_string(&parsed["vc"])?)
+        }
+    }
+
+    /// Sets document for given vc name. The VC is checked with [`check_vc`] and then stored in `vc_storage`.
     ///
     /// # Arguments
     ///
     /// * `vc_id` - vc_id to set value for
     /// * `value` - value to set
-    #[allow(dead_code)]
-    async fn set_vc_document(&mut self, _vc_id: &str, _value: &str) -> std::result::Result<(), Box<dyn std::error::Error>> {
-        unimplemented!();
+    ///
+    /// [`check_vc`]: RustVcResolverEvan#method.check_vc
+    pub async fn set_vc_document(&mut self, vc_id: &str, value: &str) -> Result<(), VadeEvanError> {
+        if self.vc_storage.is_none() {
+            return Err(VadeEvanError::Config("no vc_storage set, cannot set vc document".to_string()));
+        }
+        let parsed: Value = serde_json::from_str(value)?;
+        if parsed["id"].as_str() != Some(vc_id) {
+            return Err(VadeEvanError::Schema(format!("id of vc document does not match \"{}\"", vc_id)));
+        }
+        self.check_vc(vc_id, value).await?;
+        debug!("setting vc document for {}", vc_id);
+        match self.vc_storage.as_mut() {
+            Some(vc_storage) => vc_storage.set(vc_id, value).await,
+            None => Err(VadeEvanError::Config("no vc_storage set, cannot set vc document".to_string())),
+        }
+    }
+
+    /// Gets status of a VC, uses `status_cache` as configured in its mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_id` - vc status id / url to query
+    /// * `warnings` - warnings of current check, a warning is added if a stale status is used
+    async fn get_vc_status(&self, status_id: &str, warnings: &mut Vec<String>) -> Result<bool, VadeEvanError> {
+        let status_cache = match &self.status_cache {
+            Some(status_cache) => status_cache,
+            None => return get_vc_status_valid(self.transport.as_ref(), status_id).await,
+        };
+        let now = self.clock.now();
+        if let Some(cached) = status_cache.get(status_id, &now) {
+            debug!("using cached status for {}", status_id);
+            return Ok(cached.active);
+        }
+        match get_vc_status_valid(self.transport.as_ref(), status_id).await {
+            Ok(active) => {
+                status_cache.set(status_id, active, &self.clock.now());
+                Ok(active)
+            },
+            // responses of unreachable endpoints, e.g. error pages of proxies, are no valid statuses
+            Err(e) => match status_cache.get_last_known(status_id, &now) {
+                Some(cached) if status_cache.mode == StatusCacheMode::Degraded => {
+                    warnings.push(format!(
+                        "could not fetch status \"{}\" ({}), using status from {}",
+                        status_id,
+                        e,
+                        cached.fetched.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    ));
+                    Ok(cached.active)
+                },
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// Marks challenge of a proof as used in `nonce_store`, fails if it has been used before.
+    ///
+    /// Used challenges are forgotten after the TTL of `nonce_store`, so proofs are rejected if they
+    /// are older than that, reduced by `clock_skew`, or have been created in the future.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge` - `challenge` of proof
+    /// * `created` - signed creation time of proof
+    fn use_challenge(&self, challenge: &str, created: &DateTime<Utc>) -> Result<(), VadeEvanError> {
+        let nonce_store = self.nonce_store.as_ref()
+            .ok_or_else(|| VadeEvanError::Config("no nonce store configured".to_string()))?;
+        let now = self.clock.now();
+        if *created > now + self.clock_skew {
+            return Err(VadeEvanError::Challenge(format!(
+                "proof has been created in the future, at {}", created.to_rfc3339())));
+        }
+        if *created + nonce_store.ttl() <= now + self.clock_skew {
+            return Err(VadeEvanError::Challenge(format!(
+                "proof created at {} is too old to check its challenge for replays", created.to_rfc3339())));
+        }
+        match nonce_store.use_nonce(challenge, &now)? {
+            true => Ok(()),
+            false => Err(VadeEvanError::Challenge(format!("challenge \"{}\" has already been used", challenge))),
+        }
+    }
+
+    /// Resolves DID document from `self.did_cache` if found there, with `self.vade` otherwise.
+    /// DID documents resolved with `self.vade` are added to `self.did_cache`. DIDs not found in
+    /// `self.did_cache` are not resolved with `offline` set in `policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `did` - DID to resolve
+    async fn resolve_did(&self, did: &str) -> Result<String, VadeEvanError> {
+        if let Some(did_document) = self.did_cache.as_ref().and_then(|cache| cache.get_at(did, &self.clock.now())) {
+            debug!("using cached did document for {}", did);
+            return Ok(did_document);
+        }
+        if self.policy.offline {
+            return Err(VadeEvanError::Network(format!("offline, DID {} is not cached", did)));
+        }
+        let vade = self.vade.as_ref()
+            .ok_or_else(|| VadeEvanError::Config("no vade instance set to resolve DIDs".to_string()))?;
+        let did_document = vade.get_did_document(did).await
+            .map_err(|e| VadeEvanError::Network(format!("could not resolve DID {}, {}", did, e)))?;
+        if let Some(cache) = &self.did_cache {
+            cache.set_at(did, did_document.to_string(), &self.clock.now());
+        }
+        Ok(did_document)
     }
 
-    /// Gets all keys from given DID. `self.vade` will be queried for DID, then public keys are checked for keys.
+    /// Resolves DID document from `prefetched` if found there, like `resolve_did` otherwise. DID
+    /// documents resolved this way are added to `prefetched`.
+    ///
+    /// # Arguments
+    ///
+    /// * `did` - DID to resolve
+    /// * `prefetched` - DID documents fetched in advance
+    async fn get_did_document(&self, did: &str, prefetched: Option<&Prefetched>) -> Result<Value, VadeEvanError> {
+        let known = prefetched.and_then(|prefetched| prefetched.did_documents.borrow().get(did).cloned());
+        let did_document = match (known, prefetched) {
+            (Some(did_document), _) => did_document?,
+            (None, Some(prefetched)) => {
+                let did_document = self.resolve_did(did).await;
+                prefetched.did_documents.borrow_mut().insert(did.to_string(), did_document.clone());
+                did_document?
+            },
+            (None, None) => self.resolve_did(did).await?,
+        };
+        Ok(serde_json::from_str(&did_document)?)
+    }
+
+    /// Gets key used for a proof of given issuer, returns it and how it has been authorized.
+    ///
+    /// Starting with the issuer's DID, a key is authorized by a DID if
+    /// - the verification relationship `relationship` lists it (`authentication` is used if
+    ///   `assertionMethod` is missing), or
+    /// - the key belongs to the DID, which declares no verification relationships at all and lists
+    ///   the key in `publicKey`.
+    ///
+    /// If not authorized, the DIDs in `controller` are checked the same way, up to
+    /// [`MAX_CONTROLLER_DEPTH`] links away from the issuer. Each DID is checked only once.
     ///
     /// # Arguments
-    /// 
+    ///
+    /// * `issuer` - DID of VC issuer
     /// * `key_from_did` - key reference to a DID document like "$DID#key-1"
-    async fn get_key_from_did(&self, key_from_did: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let did = key_from_did.splitn(2, '#').next().unwrap();
-        debug!("getting keys for did {:?}", &did);
-        let did_document_string = self.vade.as_ref().unwrap().get_did_document(did).await.unwrap();
-        let did_document: EvanDid = serde_json::from_str(&did_document_string)?;
-
-        let key_objects: Vec<EvanDidPublicKey> = did_document.publicKey;
-        let matches: Vec<String> = key_objects
-            .into_iter()
-            .filter(|key| key.id == key_from_did)
-            .map(|key| key.ethereumAddress)
-            .collect();
- 
-        match matches.len() {
-            1 => Ok(format!("{}", matches[0])),
-            0 => Err(Box::from(format!("key {} not found in DID {}", key_from_did, did))),
-            _ => Err(Box::from(format!("multiple matches found for key {} in DID {}", key_from_did, did))),
+    /// * `relationship` - verification relationship the key has to be listed in, `assertionMethod`
+    ///   for VCs and `authentication` for VPs
+    /// * `prefetched` - DID documents fetched in advance, DIDs are resolved if not found there
+    async fn get_key_from_did(
+        &self,
+        issuer: &str,
+        key_from_did: &str,
+        relationship: &str,
+        prefetched: Option<&Prefetched>,
+    ) -> Result<(VerificationKey, KeyAuthorization), VadeEvanError> {
+        let key_did = key_from_did.split('#').next().unwrap_or_default();
+        let mut visited = HashSet::new();
+        let mut pending = VecDeque::new();
+        let mut reached_key_did = false;
+        let mut depth_exceeded = false;
+        visited.insert(issuer.to_string());
+        pending.push_back(vec![issuer.to_string()]);
+        while let Some(controller_path) = pending.pop_front() {
+            let did = &controller_path[controller_path.len() - 1];
+            debug!("looking for key {} in DID {}", key_from_did, did);
+            let did_document = self.get_did_document(did, prefetched).await?;
+            reached_key_did = reached_key_did || did == key_did;
+
+            let relationship = get_relationship(&did_document, relationship);
+            let authorized = match relationship {
+                Some((_, methods)) => methods.iter().any(|method| get_method_id(method, did).as_deref() == Some(key_from_did)),
+                None => did == key_did && !has_verification_relationships(&did_document),
+            };
+            if authorized {
+                let key = match relationship.and_then(|(_, methods)| methods.iter()
+                        .find(|method| method.is_object() && get_method_id(method, did).as_deref() == Some(key_from_did))) {
+                    Some(embedded) => embedded.clone(),
+                    None if did == key_did => find_public_key(&did_document, key_from_did)?,
+                    None => find_public_key(&self.get_did_document(key_did, prefetched).await?, key_from_did)?,
+                };
+                return Ok((VerificationKey::parse(&key)?, KeyAuthorization {
+                    verification_method: key_from_did.to_string(),
+                    relationship: relationship.map(|(name, _)| name.to_string()),
+                    controller_path,
+                }));
+            }
+
+            for controller in get_controllers(&did_document) {
+                if visited.contains(&controller) {
+                    continue;
+                }
+                if controller_path.len() > MAX_CONTROLLER_DEPTH {
+                    depth_exceeded = true;
+                    continue;
+                }
+                visited.insert(controller.to_string());
+                let mut path = controller_path.clone();
+                path.push(controller);
+                pending.push_back(path);
+            }
+        }
+
+        let limit = match depth_exceeded {
+            true => format!(", stopped after {} controllers", MAX_CONTROLLER_DEPTH),
+            false => String::new(),
+        };
+        match reached_key_did {
+            true => Err(VadeEvanError::KeyNotFound(format!(
+                "key {} is not authorized for {} by issuer {}{}", key_from_did, relationship, issuer, limit))),
+            false => Err(VadeEvanError::Signature(format!(
+                "verificationMethod \"{}\" does not belong to issuer \"{}\"{}", key_from_did, issuer, limit))),
+        }
+    }
+
+    /// Creates a new VC document like [`create_vc_with_signer`] with default options and a
+    /// secp256k1 key given as hex string.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_data` - partial or full VC
+    /// * `verification_method` - issuer of VC
+    /// * `private_key` - private key to create proof as 32B hex string
+    ///
+    /// [`create_vc_with_signer`]: RustVcResolverEvan#method.create_vc_with_signer
+    #[deprecated(note = "keep keys in a `Signer` like `InMemorySigner` and use `create_vc_with_signer` instead")]
+    pub async fn create_vc(
+        &self,
+        vc_data: &str,
+        verification_method: &str,
+        private_key: &str
+    ) -> Result<String, VadeEvanError> {
+        let signer = InMemorySigner::from_hex(verification_method, private_key)?;
+        self.create_vc_with_signer(vc_data, &signer, &CreateVcOptions::new()).await
+    }
+
+    /// Creates a new VC document like [`create_vc_with_signer`] or, for `Ed25519Signature2018`
+    /// proofs, [`create_vc_with_ed25519_signer`] with a key given as hex string. `private_key` has
+    /// to match `proof_type` of `options`, 32B hex strings are used for secp256k1 and Ed25519 keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_data` - partial or full VC
+    /// * `verification_method` - issuer of VC
+    /// * `private_key` - private key to create proof as 32B hex string
+    /// * `options` - options for creating VC
+    ///
+    /// [`create_vc_with_signer`]: RustVcResolverEvan#method.create_vc_with_signer
+    /// [`create_vc_with_ed25519_signer`]: RustVcResolverEvan#method.create_vc_with_ed25519_signer
+    #[deprecated(note = "keep keys in a `Signer` or `Ed25519Signer` and use `create_vc_with_signer` or `create_vc_with_ed25519_signer` instead")]
+    pub async fn create_vc_with_options(
+        &self,
+        vc_data: &str,
+        verification_method: &str,
+        private_key: &str,
+        options: &CreateVcOptions,
+    ) -> Result<String, VadeEvanError> {
+        if options.proof_type == PROOF_TYPE_ED25519_SIGNATURE_2018 {
+            let signer = InMemoryEd25519Signer::from_hex(verification_method, private_key)?;
+            return self.create_vc_with_ed25519_signer(vc_data, &signer, options).await;
         }
+        let signer = InMemorySigner::from_hex(verification_method, private_key)?;
+        self.create_vc_with_signer(vc_data, &signer, options).await
     }
 
-    /// Creates a new VC document. Will automatically add manadatory fields and proof.
+    /// Creates a new VC document signed with `signer`. Will automatically add manadatory fields and
+    /// proof. The key id of `signer` is used as `verificationMethod`.
+    ///
     /// Automatically adds the following fields if missing:
+    /// - id (requires `vc_storage`)
     /// - @context
     /// - type
     /// - issuer
     /// - validFrom
+    /// - validUntil (if `valid_for` is set in `options`)
     /// - proof
     ///
+    /// If `as_jwt` is set in `options`, the VC is returned as compact JWT-VC with its `issuer`,
+    /// `id`, `credentialSubject.id`, `validFrom` and `validUntil` (or `expirationDate`) as `iss`,
+    /// `jti`, `sub`, `nbf` and `exp` claims and the key id of `signer` as `kid`.
+    ///
+    /// `signer` holds secp256k1 keys, `Ed25519Signature2018` proofs are created with
+    /// [`create_vc_with_ed25519_signer`].
+    ///
     /// # Arguments
     ///
     /// * `vc_data` - partial or full VC
-    /// * `verification_method` - issuer of VC
-    /// * `private_key` - private key to create proof as 32B hex string
-    pub async fn create_vc(
+    /// * `signer` - signer of issuer
+    /// * `options` - options for creating VC
+    ///
+    /// [`create_vc_with_ed25519_signer`]: RustVcResolverEvan#method.create_vc_with_ed25519_signer
+    pub async fn create_vc_with_signer(
         &self,
         vc_data: &str,
-        verification_method: &str,
-        private_key: &str
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let mut parsed_vc: Value = serde_json::from_str(&vc_data).unwrap();
+        signer: &dyn Signer,
+        options: &CreateVcOptions,
+    ) -> Result<String, VadeEvanError> {
+        let (mut parsed_vc, issuer, now) = self.prepare_vc(vc_data, &signer.key_id(), options).await?;
 
-        // currently new VCs created here are offline, so id is mandatory
-        if parsed_vc["id"].is_null() {
-            return Err(Box::new(SimpleError::new("\"id\" is required for offline VCs")))
-        } 
+        if options.as_jwt {
+            return create_jwt_vc(&parsed_vc, &issuer, signer, options, &now).await;
+        }
+
+        // ensure proof
+        if parsed_vc["proof"].is_null() {
+            parsed_vc["proof"] = match options.proof_type.as_str() {
+                PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1 => create_proof(
+                    &parsed_vc, "vc", &issuer, signer, &options.jws_alg, options.challenge.as_deref(), &now).await?,
+                PROOF_TYPE_JSON_WEB_SIGNATURE_2020 => create_json_web_signature_proof(
+                    &parsed_vc, signer, options.challenge.as_deref(), &now).await?,
+                PROOF_TYPE_ED25519_SIGNATURE_2018 => return Err(VadeEvanError::Unsupported(format!(
+                    "proof type \"{}\" cannot be created with a secp256k1 signer", PROOF_TYPE_ED25519_SIGNATURE_2018))),
+                proof_type => return Err(VadeEvanError::Unsupported(format!("proof type \"{}\" is not supported", proof_type))),
+            };
+        }
+
+        // final VC document
+        let vc_str = format!("{}", &parsed_vc);
+        debug!("final VC document: {}", vc_str);
+
+        Ok(vc_str)
+    }
+
+    /// Creates a new VC document with an `Ed25519Signature2018` proof like
+    /// [`create_vc_with_signer`]. `proof_type` of `options` has to be `Ed25519Signature2018`, VCs
+    /// signed with Ed25519 keys cannot be returned as JWT-VCs.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_data` - partial or full VC
+    /// * `signer` - Ed25519 signer of issuer
+    /// * `options` - options for creating VC
+    ///
+    /// [`create_vc_with_signer`]: RustVcResolverEvan#method.create_vc_with_signer
+    pub async fn create_vc_with_ed25519_signer(
+        &self,
+        vc_data: &str,
+        signer: &dyn Ed25519Signer,
+        options: &CreateVcOptions,
+    ) -> Result<String, VadeEvanError> {
+        if options.proof_type != PROOF_TYPE_ED25519_SIGNATURE_2018 {
+            return Err(VadeEvanError::Unsupported(format!(
+                "proof type \"{}\" cannot be created with an Ed25519 signer", options.proof_type)));
+        }
+        if options.as_jwt {
+            return Err(VadeEvanError::Unsupported("JWT-VCs cannot be signed with an Ed25519 signer".to_string()));
+        }
+        let (mut parsed_vc, _, now) = self.prepare_vc(vc_data, &signer.key_id(), options).await?;
+
+        // ensure proof
+        if parsed_vc["proof"].is_null() {
+            parsed_vc["proof"] = create_ed25519_proof(&parsed_vc, signer, options.challenge.as_deref(), &now).await?;
+        }
+
+        // final VC document
+        let vc_str = format!("{}", &parsed_vc);
+        debug!("final VC document: {}", vc_str);
+
+        Ok(vc_str)
+    }
+
+    /// Adds mandatory fields to VC data as described in [`create_vc_with_signer`], returns VC
+    /// without proof, its issuer and issuing timestamp.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_data` - partial or full VC
+    /// * `verification_method` - key to sign with
+    /// * `options` - options for creating VC
+    ///
+    /// [`create_vc_with_signer`]: RustVcResolverEvan#method.create_vc_with_signer
+    async fn prepare_vc(
+        &self,
+        vc_data: &str,
+        verification_method: &str,
+        options: &CreateVcOptions,
+    ) -> Result<(Value, String, DateTime<Utc>), VadeEvanError> {
+        let mut parsed_vc: Value = serde_json::from_str(vc_data)?;
+        if !parsed_vc.is_object() {
+            return Err(VadeEvanError::Schema("vc data is not an object".to_string()));
+        }
 
         // ensure proper context
         if parsed_vc["@context"].is_null() {
             parsed_vc["@context"] = Value::from(Vec::<&str>::new());
         }
-        if !parsed_vc["@context"].as_array().unwrap().iter().any(|v| v == VC_W3C_MANDATORY_CONTEXT) {
-            parsed_vc["@context"].as_array_mut().unwrap().push(Value::from(VC_W3C_MANDATORY_CONTEXT));
-        }
+        match parsed_vc["@context"].as_array_mut() {
+            Some(context) => {
+                if !context.iter().any(|v| v == VC_W3C_MANDATORY_CONTEXT) {
+                    context.push(Value::from(VC_W3C_MANDATORY_CONTEXT));
+                }
+            },
+            None => return Err(VadeEvanError::Schema("\"@context\" has to be an array".to_string())),
+        };
 
         // ensure type
         if parsed_vc["type"].is_null() {
@@ -151,278 +968,695 @@ impl RustVcResolverEvan {
             let split: Vec<&str> = verification_method.split('#').collect();
             parsed_vc["issuer"] = Value::from(split[0]);
         }
+        let issuer = get_issuer_id(&parsed_vc)?;
+
+        // VCs without storage are offline, so id is mandatory for them
+        if parsed_vc["id"].is_null() {
+            match &self.vc_storage {
+                Some(vc_storage) => {
+                    let vc_id = vc_storage.create_id(&issuer).await?;
+                    parsed_vc["id"] = Value::from(vc_id);
+                },
+                None => return Err(VadeEvanError::Schema("\"id\" is required for offline VCs".to_string())),
+            }
+        }
 
         // ensure validFrom timestamp
-        let now: DateTime<Utc> = Utc::now();
+        let now: DateTime<Utc> = self.clock.now();
         if parsed_vc["validFrom"].is_null() {
             parsed_vc["validFrom"] = Value::from(format!("{}", now.format("%Y-%m-%dT%H:%M:%S.000Z")));
         }
 
-        // ensure proof
-        if parsed_vc["proof"].is_null() {
-            parsed_vc["proof"] = create_proof(&parsed_vc, &verification_method, &private_key, &now).unwrap();
+        // add validUntil timestamp if VC should expire
+        if let Some(valid_for) = options.valid_for {
+            if parsed_vc["validUntil"].is_null() {
+                let valid_from = parse_date(&parsed_vc, "validFrom")?.unwrap_or(now);
+                parsed_vc["validUntil"] = Value::from(format!("{}", (valid_from + valid_for).format("%Y-%m-%dT%H:%M:%S.000Z")));
+            }
         }
 
-        // final VC document
-        let vc_str = String::from(format!("{}", &parsed_vc));
-        debug!("final VC document: {}", vc_str);
+        Ok((parsed_vc, issuer, now))
+    }
 
-        Ok(vc_str)
+    /// Creates a new VP document like [`create_vp_with_signer`] with a secp256k1 key given as hex
+    /// string.
+    ///
+    /// # Arguments
+    ///
+    /// * `vcs` - VCs to present, JSON documents or compact JWT-VCs
+    /// * `verification_method` - key of holder, holder is the DID of this key
+    /// * `private_key` - private key to create proof as 32B hex string
+    /// * `challenge` - challenge given by verifier
+    /// * `domain` - domain of verifier
+    ///
+    /// [`create_vp_with_signer`]: RustVcResolverEvan#method.create_vp_with_signer
+    #[deprecated(note = "keep keys in a `Signer` like `InMemorySigner` and use `create_vp_with_signer` instead")]
+    pub async fn create_vp(
+        &self,
+        vcs: &[&str],
+        verification_method: &str,
+        private_key: &str,
+        challenge: &str,
+        domain: &str,
+    ) -> Result<String, VadeEvanError> {
+        let signer = InMemorySigner::from_hex(verification_method, private_key)?;
+        self.create_vp_with_signer(vcs, &signer, challenge, domain).await
+    }
+
+    /// Creates a new VP document presenting given VCs. The presentation is signed with an
+    /// `authentication` proof of the holder, `challenge` and `domain` of the verifier are signed
+    /// as `nonce` and `aud` claims of its `jws` and added to its proof. The key id of `signer` is
+    /// used as `verificationMethod`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vcs` - VCs to present, JSON documents or compact JWT-VCs
+    /// * `signer` - signer of holder, holder is the DID of its key id
+    /// * `challenge` - challenge given by verifier
+    /// * `domain` - domain of verifier
+    pub async fn create_vp_with_signer(
+        &self,
+        vcs: &[&str],
+        signer: &dyn Signer,
+        challenge: &str,
+        domain: &str,
+    ) -> Result<String, VadeEvanError> {
+        let verification_method = signer.key_id();
+        let mut credentials = Vec::new();
+        for vc in vcs {
+            credentials.push(match is_compact_jwt(vc) {
+                true => Value::from(vc.trim()),
+                false => serde_json::from_str(vc)?,
+            });
+        }
+        let holder = verification_method.split('#').next().unwrap_or_default();
+        let mut vp = serde_json::json!({
+            "@context": [ VC_W3C_MANDATORY_CONTEXT ],
+            "type": [ VP_DEFAULT_TYPE ],
+            "holder": holder,
+            "verifiableCredential": credentials,
+        });
+
+        let now: DateTime<Utc> = self.clock.now();
+        let claims = serde_json::json!({
+            "iat": now.timestamp(),
+            "iss": holder,
+            "aud": domain,
+            "nonce": challenge,
+            "vp": vp,
+        });
+        let jws = create_jwt(&claims, signer, JWS_ALG_ES256K_R, None).await?;
+        vp["proof"] = serde_json::json!({
+            "type": PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1,
+            "created": format!("{}", now.format("%Y-%m-%dT%H:%M:%S.000Z")),
+            "proofPurpose": VP_PROOF_PURPOSE,
+            "verificationMethod": verification_method,
+            "challenge": challenge,
+            "domain": domain,
+            "jws": jws,
+        });
+
+        Ok(vp.to_string())
+    }
+
+    /// Checks given VP document. A VP is valid if
+    /// - its proof has been created by its `holder` with a key authorized for `authentication`
+    /// - its proof has been created for given `challenge` and `domain`
+    /// - `challenge` has not been used before, if a `nonce_store` is configured; VPs signed (`iat`)
+    ///   longer ago than the TTL of `nonce_store` minus `clock_skew` are rejected
+    /// - all of its VCs are valid, as checked with [`check_vcs`]
+    ///
+    /// # Arguments
+    ///
+    /// * `vp` - VP document to check
+    /// * `challenge` - challenge the VP has to be created for
+    /// * `domain` - domain the VP has to be created for
+    ///
+    /// [`check_vcs`]: RustVcResolverEvan#method.check_vcs
+    pub async fn check_vp(&self, vp: &str, challenge: &str, domain: &str) -> Result<(), VadeEvanError> {
+        let (vp_without_proof, vp_proof) = split_proof(vp)?;
+        let vp_proof = vp_proof
+            .ok_or_else(|| VadeEvanError::Signature("vp has no proof".to_string()))?;
+        if vp_proof.proof_type != PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1 {
+            return Err(VadeEvanError::Unsupported(format!(
+                "proof type \"{}\" is not supported for vps", vp_proof.proof_type)));
+        }
+        if vp_proof.proof_purpose != VP_PROOF_PURPOSE {
+            return Err(VadeEvanError::Signature(format!(
+                "proofPurpose of vps has to be \"{}\"", VP_PROOF_PURPOSE)));
+        }
+        if vp_proof.proof["challenge"] != challenge || vp_proof.proof["domain"] != domain {
+            return Err(VadeEvanError::Challenge(format!(
+                "vp has not been created for challenge \"{}\" and domain \"{}\"", challenge, domain)));
+        }
+        let holder = vp_without_proof["holder"].as_str()
+            .ok_or_else(|| VadeEvanError::Schema("vp has no \"holder\"".to_string()))?;
+
+        debug!("checking proof of vp document");
+        let (key_from_did, _) = self.get_key_from_did(
+            holder, &vp_proof.verification_method, VP_PROOF_PURPOSE, None).await?;
+        let decoded_payload_text = match get_jwt_algorithm(&vp_proof.jws)?.as_str() {
+            JWS_ALG_ES256K => verify_jwt(&vp_proof.jws, &key_from_did)?,
+            _ => {
+                let (address, decoded_payload_text) = recover_address_and_data(&vp_proof.jws)?;
+                if format!("0x{}", address) != key_from_did.ethereum_address()? {
+                    return Err(VadeEvanError::Signature("could not verify signature of vp".to_string()));
+                }
+                decoded_payload_text
+            },
+        };
+        let claims: Value = serde_json::from_str(&decoded_payload_text)?;
+        if claims["nonce"] != challenge || claims["aud"] != domain {
+            return Err(VadeEvanError::Challenge(
+                "signed challenge and domain do not match challenge and domain of proof".to_string()));
+        }
+        if canonicalize_json(&claims["vp"])? != canonicalize_json(&vp_without_proof)? {
+            return Err(VadeEvanError::Signature("recovered VP document and given VP document do not match".to_string()));
+        }
+        if self.nonce_store.is_some() {
+            let issued = claims["iat"].as_i64()
+                .and_then(|iat| Utc.timestamp_opt(iat, 0).single())
+                .ok_or_else(|| VadeEvanError::Schema("jws payload of vp has no valid \"iat\"".to_string()))?;
+            self.use_challenge(challenge, &issued)?;
+        }
+
+        debug!("checking vcs of vp document");
+        let vcs = match &vp_without_proof["verifiableCredential"] {
+            Value::Array(vcs) => vcs.to_vec(),
+            Value::Null => Vec::new(),
+            vc => vec![vc.clone()],
+        };
+        let vcs: Vec<(String, String)> = vcs.iter()
+            .map(|vc| match vc {
+                Value::String(jwt) => (String::new(), jwt.to_string()),
+                vc => (vc["id"].as_str().unwrap_or_default().to_string(), vc.to_string()),
+            })
+            .collect();
+        let vcs: Vec<(&str, &str)> = vcs.iter().map(|(id, vc)| (id.as_str(), vc.as_str())).collect();
+        for result in self.check_vcs(&vcs).await {
+            result?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for RustVcResolverEvan {
+    fn default() -> RustVcResolverEvan {
+        RustVcResolverEvan::new()
     }
 }
 
 
 #[async_trait(?Send)]
 impl VcResolver for RustVcResolverEvan {
-    /// Checks given Vc document.
-    /// A Vc document is considered as valid if returning ().
-    /// Resolver may throw to indicate
-    /// - that it is not responsible for this Vc
-    /// - that it considers this Vc as invalid
-    /// 
-    /// Currently the test `vc_id` `"test"` is accepted as valid.
+    /// Checks given Vc document, see [`RustVcResolverEvan::check_vc`].
     ///
     /// # Arguments
     ///
     /// * `vc_id` - vc_id to check document for
     /// * `value` - value to check
     async fn check_vc(&self, vc_id: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: add some pre-flight checks (key type, etc)
-        let mut vc: Value = serde_json::from_str(value)?;
-        if vc["proof"].is_null() {
-            debug!("vcs without a proof are considered as valid");
-            Ok(())
-        } else {
-            debug!("checking vc document");
-
-            // separate proof and vc document (vc document will be a Map after this)
-            let vc_without_proof = vc.as_object_mut().unwrap();
-            let vc_proof =  vc_without_proof.remove("proof").unwrap();
-
-            // recover address and payload text (pure jwt format)
-            let (address, decoded_payload_text) = recover_address_and_data(vc_proof["jws"].as_str().unwrap())?;
-
-            debug!("checking if document given and document from jws are equal");
-            // fetch recovered vc document (without proof from jwt)
-            let re = Regex::new(JWT_REGEX).unwrap();
-            let caps = re.captures(&decoded_payload_text).unwrap();
-            // parse recovered vc document into serde Map
-            let parsed_caps1: Value = serde_json::from_str(&caps[1])?;
-            let parsed_caps1_map = parsed_caps1.as_object().unwrap();
-            // compare documents
-            if vc_without_proof != parsed_caps1_map {
-                return Err(Box::from("recovered VC document and given VC document do not match"));
-            }
-
-            debug!("checking proof of vc document");
-            let address = format!("0x{}", address);
-            let key_to_use = vc_proof["verificationMethod"].as_str().unwrap();
-            debug!("recovered address: {}", &address);
-            debug!("key to use for verification: {}", &key_to_use);
-            let key_from_did = self.get_key_from_did(key_to_use).await?;
-            debug!("key from did: {}", &key_from_did);
-            if address != key_from_did {
-                return Err(Box::from(format!("could not verify signature of \"{}\"", vc_id)));
-            }
-
-            debug!("checking if credential status is present, query it");
-            if !vc["credentialStatus"].is_null()
-                    && vc["credentialStatus"]["type"] == "evan:evanCredential" {
-                debug!("credential status is present, query it");
-                let vc_status = get_vc_status_valid(vc["credentialStatus"]["id"].as_str().unwrap()).await?;
-                if !vc_status {
-                  return Err(Box::from(format!("vc \"{}\" is not active", &vc_id)));
-                }
-            }
-            
-            debug!("vc document is valid");
-            Ok(())
-        }
+        Ok(RustVcResolverEvan::check_vc(self, vc_id, value).await?)
     }
 
-    /// Gets document for given vc name.
+    /// Gets document for given vc name, see [`RustVcResolverEvan::get_vc_document`].
     ///
     /// # Arguments
     ///
     /// * `vc_id` - vc_id to fetch
     async fn get_vc_document(&self, vc_id: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let body = reqwest::get(&format!("https://testcore.evan.network/vc/{}", vc_id))
-            .await?
-            .text()
-            .await?;
-        let parsed: Value = serde_json::from_str(&body).unwrap();
-        if parsed["status"] == "error" {
-            Err(Box::new(SimpleError::new(format!("could not get vc document, {:?}", parsed["error"].as_str().unwrap()))))
-        } else {
-            Ok(serde_json::to_string(&parsed["vc"]).unwrap())
-        }
+        Ok(RustVcResolverEvan::get_vc_document(self, vc_id).await?)
     }
-    
-    /// Sets document for given vc name.
+
+    /// Sets document for given vc name, see [`RustVcResolverEvan::set_vc_document`].
     ///
     /// # Arguments
     ///
     /// * `vc_name` - vc_name to set value for
     /// * `value` - value to set
-    async fn set_vc_document(&mut self, _vc_name: &str, _value: &str) -> Result<(), Box<dyn std::error::Error>> {
-        unimplemented!();
+    async fn set_vc_document(&mut self, vc_name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(RustVcResolverEvan::set_vc_document(self, vc_name, value).await?)
     }
 }
 
-/// Fetches revokation status for VCs. VCs can be active or revoked (-> true/false)
-/// missing VC documents or other errors are indicated as Errors.
+/// Gets id of VC issuer, `issuer` may be given as DID or as object with an `id`.
 ///
 /// # Arguments
 ///
-/// * `vc_status_id` - vc status id / url to query
-async fn get_vc_status_valid(vc_status_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
-    let body = reqwest::get(vc_status_id)
-        .await?
-        .text()
-        .await?;
-    let parsed: Value = serde_json::from_str(&body).unwrap();
-    if parsed["status"] == "error" {
-        Err(Box::new(SimpleError::new(format!("vc status error, {:?}", parsed["error"].as_str().unwrap()))))
-    } else {
-        let is_active = match parsed["vcStatus"].as_str().unwrap() {
-            "active" => true,
-            _ => false,
+/// * `vc` - VC document to get issuer from
+fn get_issuer_id(vc: &Value) -> Result<String, VadeEvanError> {
+    match &vc["issuer"] {
+        Value::String(issuer) => Ok(issuer.to_string()),
+        Value::Object(issuer) => match issuer.get("id").and_then(|id| id.as_str()) {
+            Some(issuer) => Ok(issuer.to_string()),
+            None => Err(VadeEvanError::Schema("\"issuer\" has no \"id\"".to_string())),
+        },
+        _ => Err(VadeEvanError::Schema("\"issuer\" has to be a DID or an object with an \"id\"".to_string())),
+    }
+}
+
+/// Checks that `alg` of a JWS is supported, i.e. `ES256K-R`, `ES256K` or `EdDSA`.
+///
+/// # Arguments
+///
+/// * `alg` - `alg` from JWS header
+fn check_jws_algorithm(alg: &str) -> Result<(), VadeEvanError> {
+    match alg {
+        JWS_ALG_ES256K_R | JWS_ALG_ES256K | JWS_ALG_EDDSA => Ok(()),
+        _ => Err(VadeEvanError::Unsupported(format!("jws algorithm \"{}\" is not supported", alg))),
+    }
+}
+
+/// Checks that `proofPurpose` of a proof is a known purpose.
+///
+/// # Arguments
+///
+/// * `proof_purpose` - `proofPurpose` of proof
+fn check_proof_purpose(proof_purpose: &str) -> Result<(), VadeEvanError> {
+    match proof_purpose {
+        VC_PROOF_PURPOSE | VP_PROOF_PURPOSE => Ok(()),
+        _ => Err(VadeEvanError::Signature(format!("unknown proofPurpose \"{}\"", proof_purpose))),
+    }
+}
+
+/// Gets verification relationship of DID document, returns its name and listed verification
+/// methods. `authentication` is used for `assertionMethod` if the DID document has no
+/// `assertionMethod`.
+///
+/// # Arguments
+///
+/// * `did_document` - DID document to get relationship from
+/// * `relationship` - name of relationship, e.g. `"assertionMethod"`
+fn get_relationship<'a>(did_document: &'a Value, relationship: &'a str) -> Option<(&'a str, &'a Vec<Value>)> {
+    let fallback = match relationship {
+        VC_PROOF_PURPOSE => Some(VP_PROOF_PURPOSE),
+        _ => None,
+    };
+    std::iter::once(relationship).chain(fallback)
+        .filter_map(|name| did_document[name].as_array().map(|methods| (name, methods)))
+        .next()
+}
+
+/// Checks if DID document declares any verification relationship.
+///
+/// # Arguments
+///
+/// * `did_document` - DID document to check
+fn has_verification_relationships(did_document: &Value) -> bool {
+    VERIFICATION_RELATIONSHIPS.iter().any(|name| !did_document[name].is_null())
+}
+
+/// Gets absolute id of a verification method, given as reference or as embedded key.
+///
+/// # Arguments
+///
+/// * `method` - entry of a verification relationship
+/// * `did` - DID of document listing the method, relative ids like `"#key-1"` are resolved against it
+fn get_method_id(method: &Value, did: &str) -> Option<String> {
+    match method {
+        Value::String(id) => Some(get_absolute_key_id(did, id)),
+        _ => method["id"].as_str().map(|id| get_absolute_key_id(did, id)),
+    }
+}
+
+/// Gets DIDs listed in `controller` of DID document, which may be a single DID or an array.
+///
+/// # Arguments
+///
+/// * `did_document` - DID document to get controllers from
+fn get_controllers(did_document: &Value) -> Vec<String> {
+    match &did_document["controller"] {
+        Value::String(controller) => vec![controller.to_string()],
+        Value::Array(controllers) => controllers.iter()
+            .filter_map(|controller| controller.as_str().map(|controller| controller.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Finds key in `publicKey` of DID document.
+///
+/// # Arguments
+///
+/// * `did_document` - DID document to search
+/// * `key_from_did` - key reference like "$DID#key-1", keys with relative ids like `"#key-1"` match
+///   as well
+fn find_public_key(did_document: &Value, key_from_did: &str) -> Result<Value, VadeEvanError> {
+    let did = key_from_did.split('#').next().unwrap_or_default();
+    let matches: Vec<&Value> = did_document["publicKey"].as_array()
+        .map(|keys| keys.iter()
+            .filter(|key| key["id"].as_str().map(|id| get_absolute_key_id(did, id)).as_deref() == Some(key_from_did))
+            .collect())
+        .unwrap_or_default();
+    match matches.len() {
+        1 => Ok(matches[0].clone()),
+        0 => Err(VadeEvanError::KeyNotFound(format!("key {} not found in DID {}", key_from_did, did))),
+        _ => Err(VadeEvanError::KeyNotFound(format!("multiple matches found for key {} in DID {}", key_from_did, did))),
+    }
+}
+
+/// Gets `type`s of VC, which may be given as a single string or as an array.
+///
+/// # Arguments
+///
+/// * `vc` - VC document
+fn get_credential_types(vc: &Value) -> Result<Vec<String>, VadeEvanError> {
+    match &vc["type"] {
+        Value::String(credential_type) => Ok(vec![credential_type.to_string()]),
+        Value::Array(credential_types) => credential_types.iter()
+            .map(|credential_type| credential_type.as_str()
+                .map(|credential_type| credential_type.to_string())
+                .ok_or_else(|| VadeEvanError::Schema("\"type\" has to contain strings".to_string())))
+            .collect(),
+        _ => Err(VadeEvanError::Schema("\"type\" has to be a string or an array".to_string())),
+    }
+}
+
+/// Verifies signature of a proof that does not allow recovering its signer. Returns payload of
+/// `jws` for proofs with embedded documents, `None` for detached JWS.
+///
+/// # Arguments
+///
+/// * `vc` - VC document without proof
+/// * `vc_proof` - proof of VC
+/// * `key` - key of proof's `verificationMethod`
+fn verify_proof_signature(vc: &Value, vc_proof: &ProofParts, key: &VerificationKey) -> Result<Option<String>, VadeEvanError> {
+    match vc_proof.proof_type.as_str() {
+        PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1 => verify_jwt(&vc_proof.jws, key).map(Some),
+        PROOF_TYPE_ED25519_SIGNATURE_2018 | PROOF_TYPE_JSON_WEB_SIGNATURE_2020 =>
+            verify_detached_jws_proof(vc, &vc_proof.proof, key).map(|_| None),
+        proof_type => Err(VadeEvanError::Unsupported(format!("proof type \"{}\" is not supported", proof_type))),
+    }
+}
+
+/// Properties of a VC proof needed for checking it.
+struct ProofParts {
+    proof: Value,
+    proof_type: String,
+    proof_purpose: String,
+    jws: String,
+    verification_method: String,
+}
+
+/// Parses VC document and separates it from its proof.
+///
+/// Returns VC without proof and, if VC has a proof, `type`, `proofPurpose`, `jws` and `verificationMethod`
+/// of its proof. Compact JWT-VCs are parsed with [`split_jwt_vc`].
+///
+/// # Arguments
+///
+/// * `value` - VC document to parse
+fn split_proof(value: &str) -> Result<(Value, Option<ProofParts>), VadeEvanError> {
+    if is_compact_jwt(value) {
+        return split_jwt_vc(value.trim()).map(|(vc, vc_proof)| (vc, Some(vc_proof)));
+    }
+    let mut vc: Value = serde_json::from_str(value)?;
+    let vc_object = vc.as_object_mut()
+        .ok_or_else(|| VadeEvanError::Schema("vc document is not an object".to_string()))?;
+    let vc_proof = match vc_object.remove("proof") {
+        Some(Value::Null) | None => return Ok((vc, None)),
+        Some(vc_proof) => vc_proof,
+    };
+    let proof_type = vc_proof["type"].as_str()
+        .ok_or_else(|| VadeEvanError::Schema("proof has no \"type\"".to_string()))?
+        .to_string();
+    let proof_purpose = vc_proof["proofPurpose"].as_str()
+        .unwrap_or(VC_PROOF_PURPOSE)
+        .to_string();
+    let jws = vc_proof["jws"].as_str()
+        .ok_or_else(|| VadeEvanError::Schema("proof has no \"jws\"".to_string()))?
+        .to_string();
+    let verification_method = vc_proof["verificationMethod"].as_str()
+        .ok_or_else(|| VadeEvanError::Schema("proof has no \"verificationMethod\"".to_string()))?
+        .to_string();
+
+    Ok((vc, Some(ProofParts { proof: vc_proof, proof_type, proof_purpose, jws, verification_method })))
+}
+
+/// Checks if given value is a compact JWT rather than a JSON document.
+///
+/// # Arguments
+///
+/// * `value` - value to check
+fn is_compact_jwt(value: &str) -> bool {
+    let value = value.trim();
+    !value.starts_with('{') && value.split('.').count() == 3
+}
+
+/// Parses compact JWT-VC, returns VC from its claims (see [`vc_from_jwt_claims`]) and its JWT as
+/// `EcdsaPublicKeySecp256k1` proof with `kid` from header as `verificationMethod`.
+///
+/// # Arguments
+///
+/// * `jwt` - compact JWT-VC
+fn split_jwt_vc(jwt: &str) -> Result<(Value, ProofParts), VadeEvanError> {
+    let (header, claims) = decode_jwt(jwt)?;
+    let verification_method = header["kid"].as_str()
+        .ok_or_else(|| VadeEvanError::Schema("jwt header has no \"kid\"".to_string()))?
+        .to_string();
+    let vc = vc_from_jwt_claims(&claims)?;
+    let proof = serde_json::json!({
+        "type": PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1,
+        "proofPurpose": VC_PROOF_PURPOSE,
+        "verificationMethod": verification_method,
+        "jws": jwt,
+    });
+
+    Ok((vc, ProofParts {
+        proof,
+        proof_type: PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1.to_string(),
+        proof_purpose: VC_PROOF_PURPOSE.to_string(),
+        jws: jwt.to_string(),
+        verification_method,
+    }))
+}
+
+/// Gets VC from `vc` claim of a JWT. Registered claims are added to it if missing there and
+/// have to match it otherwise:
+/// - `iss` as `issuer`
+/// - `jti` as `id`
+/// - `sub` as `credentialSubject.id`
+/// - `nbf` as `validFrom` (unless VC has `issuanceDate`)
+/// - `exp` as `validUntil` (unless VC has `expirationDate`)
+///
+/// # Arguments
+///
+/// * `claims` - JWT payload
+fn vc_from_jwt_claims(claims: &Value) -> Result<Value, VadeEvanError> {
+    let mut vc = claims.get("vc")
+        .filter(|vc| vc.is_object())
+        .ok_or_else(|| VadeEvanError::Parse("could not find vc document in jws payload".to_string()))?
+        .clone();
+    let mismatch = |claim: &str, property: &str| VadeEvanError::Schema(format!(
+        "jwt claim \"{}\" does not match \"{}\" of vc", claim, property));
+
+    if let Some(iss) = claims.get("iss") {
+        match vc["issuer"].is_null() {
+            true => vc["issuer"] = iss.clone(),
+            false if get_issuer_id(&vc)? != iss.as_str().unwrap_or_default() => return Err(mismatch("iss", "issuer")),
+            false => (),
+        }
+    }
+    if let Some(jti) = claims.get("jti") {
+        match vc["id"].is_null() {
+            true => vc["id"] = jti.clone(),
+            false if &vc["id"] != jti => return Err(mismatch("jti", "id")),
+            false => (),
+        }
+    }
+    if let Some(sub) = claims.get("sub") {
+        match &vc["credentialSubject"] {
+            Value::Null => vc["credentialSubject"] = serde_json::json!({ "id": sub }),
+            Value::Object(subject) => match subject.get("id") {
+                None => vc["credentialSubject"]["id"] = sub.clone(),
+                Some(id) if id != sub => return Err(mismatch("sub", "credentialSubject.id")),
+                Some(_) => (),
+            },
+            // subjects of VCs about multiple subjects cannot be mapped
+            _ => (),
+        }
+    }
+    for (claim, property, alternative) in &[("nbf", "validFrom", "issuanceDate"), ("exp", "validUntil", "expirationDate")] {
+        let timestamp = match claims.get(*claim) {
+            Some(timestamp) => timestamp.as_i64()
+                .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single())
+                .ok_or_else(|| VadeEvanError::Schema(format!("jwt claim \"{}\" is not a timestamp", claim)))?,
+            None => continue,
         };
-        Ok(is_active)
+        match parse_date(&vc, property)? {
+            None if vc[*alternative].is_null() =>
+                vc[*property] = Value::from(format!("{}", timestamp.format("%Y-%m-%dT%H:%M:%S.000Z"))),
+            Some(date) if date.timestamp() != timestamp.timestamp() => return Err(mismatch(claim, property)),
+            _ => (),
+        }
     }
+
+    Ok(vc)
 }
 
-/// Creates proof for VC document
+/// Creates compact JWT-VC for a VC, registered claims are taken from the VC as described in
+/// [`vc_from_jwt_claims`].
 ///
 /// # Arguments
 ///
-/// * `vc` - vc to create proof for
-/// * `verification_method` - issuer of VC
-/// * `private_key` - private key to create proof as 32B hex string
-/// * `now` - timestamp of issuing, may have also been used to determine `validFrom` in VC
-fn create_proof(
+/// * `vc` - VC document without proof
+/// * `issuer` - DID of issuer
+/// * `signer` - signer to sign with, its key id is used as `kid`
+/// * `options` - options for creating VC
+/// * `now` - timestamp of issuing
+async fn create_jwt_vc(
     vc: &Value,
-    verification_method: &str,
-    private_key: &str,
-    now: &DateTime<Utc>
-) -> Result<Value, Box<dyn std::error::Error>> {
-    // create to-be-signed jwt
-    let header_str = r#"{"typ":"JWT","alg":"ES256K-R"}"#;
-    let padded = BASE64URL.encode(header_str.as_bytes());
-    let header_encoded = padded.trim_end_matches('=');
-    debug!("header base64 url encdoded: {:?}", &header_encoded);
-
-    // build data object and hash
-    let mut data_json: Value = serde_json::from_str("{}").unwrap();
-    let vc_clone: Value = serde_json::from_str(&format!("{}", &vc)).unwrap();
-    data_json["iat"] = Value::from(now.timestamp());
-    data_json["vc"] = vc_clone;
-    data_json["iss"] = Value::from(vc["issuer"].as_str().unwrap());
-    let padded = BASE64URL.encode(format!("{}", &data_json).as_bytes());
-    let data_encoded = padded.trim_end_matches('=');
-    debug!("data base64 url encdoded: {:?}", &data_encoded);
-
-    // create hash of data (including header)
-    let header_and_data = format!("{}.{}", header_encoded, data_encoded);
-    let mut hasher = Sha256::new();
-    hasher.input(&header_and_data);
-    let hash = hasher.result();
-    debug!("header_and_data hash {:?}", hash);
-
-    // sign this hash
-    let hash_arr: [u8; 32] = hash.try_into().expect("slice with incorrect length");
-    let message = Message::parse(&hash_arr);
-    let mut private_key_arr = [0u8; 32];
-    hex::decode_to_slice(&private_key, &mut private_key_arr).expect("private key invalid");
-    let secret_key = SecretKey::parse(&private_key_arr)?;
-    let (sig, rec): (Signature, _) = sign(&message, &secret_key);
-    // sig to bytes (len 64), append recoveryid
-    let signature_arr = &sig.serialize();
-    let mut sig_and_rec: [u8; 65] = [0; 65];
-    for i in 0..64 {
-        sig_and_rec[i] = signature_arr[i];
-    }
-    sig_and_rec[64] = rec.serialize();
-    let padded = BASE64URL.encode(&sig_and_rec);
-    let sig_base64url = padded.trim_end_matches('=');
-    debug!("signature base64 url encdoded: {:?}", &sig_base64url);
-
-    // build proof property as serde object
-    let jws = format!("{}.{}", &header_and_data, sig_base64url);
-    let utc_now = format!("{}", now.format("%Y-%m-%dT%H:%M:%S.000Z"));
-    let proof_json_str = format!(r###"{{
-        "type": "EcdsaPublicKeySecp256k1",
-        "created": "{}",
-        "proofPurpose": "assertionMethod",
-        "verificationMethod": "{}",
-        "jws": "{}"
-    }}"###, &utc_now, &verification_method, &jws);
-    let proof: Value = serde_json::from_str(&proof_json_str).unwrap();
-
-    Ok(proof)
+    issuer: &str,
+    signer: &dyn Signer,
+    options: &CreateVcOptions,
+    now: &DateTime<Utc>,
+) -> Result<String, VadeEvanError> {
+    if options.proof_type != PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1 {
+        return Err(VadeEvanError::Unsupported(format!(
+            "JWT-VCs cannot be signed with proof type \"{}\"", options.proof_type)));
+    }
+    if options.challenge.is_some() {
+        return Err(VadeEvanError::Unsupported("JWT-VCs cannot be created with a challenge".to_string()));
+    }
+    let mut vc = vc.clone();
+    if let Some(vc) = vc.as_object_mut() {
+        vc.remove("proof");
+    }
+    let mut claims = serde_json::json!({
+        "iss": issuer,
+        "jti": vc["id"],
+        "iat": now.timestamp(),
+    });
+    if let Some(sub) = vc["credentialSubject"].get("id") {
+        claims["sub"] = sub.clone();
+    }
+    if let Some(valid_from) = parse_date(&vc, "validFrom")? {
+        claims["nbf"] = Value::from(valid_from.timestamp());
+    }
+    let valid_until = match parse_date(&vc, "validUntil")? {
+        Some(valid_until) => Some(valid_until),
+        None => parse_date(&vc, "expirationDate")?,
+    };
+    if let Some(valid_until) = valid_until {
+        claims["exp"] = Value::from(valid_until.timestamp());
+    }
+    claims["vc"] = vc;
+
+    create_jwt(&claims, signer, &options.jws_alg, Some(&signer.key_id())).await
 }
 
-/// Recovers Ethereum address of signer and data part of a jwt.
+/// Parses date property of VC, returns `None` if property is missing.
 ///
 /// # Arguments
 ///
-/// * `jwt` - jwt as str&
-fn recover_address_and_data(jwt: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
-    // jwt text parsing
-    let split: Vec<&str> = jwt.split('.').collect();
-    let (header, data, signature) = (split[0], split[1], split[2]);
-    let header_and_data = format!("{}.{}", header, data);
-    
-    // recover data for later checks
-    let data_decoded = match BASE64URL.decode(data.as_bytes()) {
-        Ok(decoded) => decoded,
-        Err(_) => match BASE64URL.decode(format!("{}=", data).as_bytes()) {
-            Ok(decoded) => decoded,
-            Err(_) => match BASE64URL.decode(format!("{}==", data).as_bytes()) {
-                Ok(decoded) => decoded,
-                Err(_) => BASE64URL.decode(format!("{}===", data).as_bytes()).unwrap(),
-            },
+/// * `vc` - VC document
+/// * `property` - name of property, e.g. `"validFrom"`
+fn parse_date(vc: &Value, property: &str) -> Result<Option<DateTime<Utc>>, VadeEvanError> {
+    match &vc[property] {
+        Value::Null => Ok(None),
+        Value::String(date) => match DateTime::parse_from_rfc3339(date) {
+            Ok(date) => Ok(Some(date.with_timezone(&Utc))),
+            Err(_) => Err(VadeEvanError::Schema(format!("\"{}\" is not a valid date", property))),
         },
-    };
-    let data_string = String::from_utf8(data_decoded)?;
-
-    // decode signature for validation
-    let signature_decoded = match BASE64URL.decode(signature.as_bytes()) {
-        Ok(decoded) => decoded,
-        Err(_) => match BASE64URL.decode(format!("{}=", signature).as_bytes()) {
-            Ok(decoded) => decoded,
-            Err(_) => BASE64URL.decode(format!("{}==", signature).as_bytes()).unwrap(),
+        _ => Err(VadeEvanError::Schema(format!("\"{}\" has to be a date string", property))),
+    }
+}
+
+/// Checks if given time is within validity period of VC, returns `false` if VC has no validity period.
+///
+/// # Arguments
+///
+/// * `vc` - VC document
+/// * `now` - time to check
+/// * `clock_skew` - tolerated difference to clock of issuer
+fn check_validity_period(vc: &Value, now: &DateTime<Utc>, clock_skew: &Duration) -> Result<bool, VadeEvanError> {
+    let valid_from = parse_date(vc, "validFrom")?;
+    let mut checked = false;
+    if let Some(valid_from) = valid_from {
+        if *now + *clock_skew < valid_from {
+            return Err(VadeEvanError::Validity(format!("vc is not valid before {}", valid_from.to_rfc3339())));
+        }
+        checked = true;
+    }
+    for property in &["validUntil", "expirationDate"] {
+        if let Some(valid_until) = parse_date(vc, property)? {
+            if *now - *clock_skew > valid_until {
+                return Err(VadeEvanError::Validity(format!("vc has expired at {}", valid_until.to_rfc3339())));
+            }
+            checked = true;
+        }
+    }
+
+    Ok(checked)
+}
+
+/// Checks if VC document signed in JWT payload equals given VC document. Documents are compared in
+/// their canonical form, so claims and properties may be in any order. Registered claims are
+/// applied to the signed VC document like for JWT-VCs, see [`vc_from_jwt_claims`].
+///
+/// # Arguments
+///
+/// * `vc_without_proof` - VC document without proof
+/// * `decoded_payload_text` - decoded payload of `jws`
+fn check_document_equality(vc_without_proof: &Value, decoded_payload_text: &str) -> Result<(), VadeEvanError> {
+    // fetch recovered vc document (without proof from jwt)
+    let claims: Value = serde_json::from_str(decoded_payload_text)
+        .map_err(|e| VadeEvanError::Parse(format!("jws payload is not a JSON object, {}", e)))?;
+    let signed_vc = vc_from_jwt_claims(&claims)?;
+    // compare documents
+    if canonicalize_json(&signed_vc)? != canonicalize_json(vc_without_proof)? {
+        return Err(VadeEvanError::Signature("recovered VC document and given VC document do not match".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Checks if proof options (proof without `jws`) of a proof are covered by its JWT, i.e. match the
+/// `proof` claim. JWTs without `proof` claim, e.g. of JWT-VCs or proofs created by earlier
+/// versions, are accepted for proofs without `challenge` only.
+///
+/// # Arguments
+///
+/// * `proof` - proof to check
+/// * `decoded_payload_text` - payload of JWT in `jws` of proof
+fn check_proof_options(proof: &Value, decoded_payload_text: &str) -> Result<(), VadeEvanError> {
+    let claims: Value = serde_json::from_str(decoded_payload_text)
+        .map_err(|e| VadeEvanError::Parse(format!("jws payload is not a JSON object, {}", e)))?;
+    match claims.get("proof") {
+        Some(signed_proof) => {
+            let mut proof_options = proof.clone();
+            if let Some(proof_options) = proof_options.as_object_mut() {
+                proof_options.remove("jws");
+            }
+            if canonicalize_json(signed_proof)? != canonicalize_json(&proof_options)? {
+                return Err(VadeEvanError::Signature("proof options do not match signed proof options".to_string()));
+            }
         },
-    };
-    debug!("signature_decoded {:?}", &signature_decoded);
-    debug!("signature_decoded.len {:?}", signature_decoded.len());
-
-    // create hash of data (including header)
-    let mut hasher = Sha256::new();
-    hasher.input(&header_and_data);
-    let hash = hasher.result();
-    debug!("header_and_data hash {:?}", hash);
-
-    // prepare arguments for public key recovery
-    let hash_arr: [u8; 32] = hash.try_into().expect("header_and_data hash invalid"); 
-    let ctx_msg = Message::parse(&hash_arr);
-    let mut signature_array = [0u8; 64];
-    for i in 0..64 {
-        signature_array[i] = signature_decoded[i];
-    }
-    // slice signature and recovery for recovery
-    debug!("recovery id: {}", signature_decoded[64]);
-    let ctx_sig = Signature::parse(&signature_array);
-    let recovery_id = RecoveryId::parse(signature_decoded[64]).unwrap();
-
-    // recover public key, build ethereum address from it
-    let recovered_key = recover(&ctx_msg, &ctx_sig, &recovery_id).unwrap();
-    let mut hasher = Keccak256::new();
-    hasher.input(&recovered_key.serialize()[1..65]);
-    let hash = hasher.result();
-    debug!("recovered_key hash {:?}", hash);
-    let address = hex::encode(&hash[12..32]);
-    debug!("address 0x{}", &address);
-
-    Ok((address, data_string))
+        None if proof.get("challenge").is_some() => return Err(VadeEvanError::Challenge(
+            "challenge of proof is not covered by its signature".to_string())),
+        None => (),
+    }
+
+    Ok(())
+}
+
+/// Fetches revokation status for VCs. VCs can be active or revoked (-> true/false)
+/// missing VC documents or other errors are indicated as Errors.
+///
+/// # Arguments
+///
+/// * `transport` - transport to send request with
+/// * `vc_status_id` - vc status id / url to query
+async fn get_vc_status_valid(transport: &dyn Transport, vc_status_id: &str) -> Result<bool, VadeEvanError> {
+    let body = transport.get(vc_status_id).await?;
+    let parsed: Value = serde_json::from_str(&body)?;
+    if parsed["status"] == "error" {
+        Err(VadeEvanError::Network(format!("vc status error, {:?}", parsed["error"].as_str().unwrap_or_default())))
+    } else {
+        match parsed["vcStatus"].as_str() {
+            Some(vc_status) => Ok(vc_status == "active"),
+            None => Err(VadeEvanError::Parse("vc status response has no \"vcStatus\"".to_string())),
+        }
+    }
 }
@@ -0,0 +1,239 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! In-memory LRU cache with per-entry TTL, used by resolvers to avoid repeated requests to nodes.
+
+use chrono::{ DateTime, Duration, Utc };
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Counters for cache usage.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CacheStats {
+    /// number of lookups answered from cache
+    pub hits: u64,
+    /// number of lookups for missing or expired entries
+    pub misses: u64,
+    /// number of entries dropped to make room for new ones
+    pub evictions: u64,
+}
+
+struct CacheEntry<V> {
+    value: V,
+    expires: DateTime<Utc>,
+    last_used: u64,
+}
+
+struct CacheState<V> {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<String, CacheEntry<V>>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+/// LRU cache with a maximum number of entries and a TTL per entry. Clones share their entries, so a
+/// clone can be handed to a resolver while the original is used to invalidate entries or to read stats.
+pub struct LruCache<V: Clone> {
+    state: Rc<RefCell<CacheState<V>>>,
+}
+
+impl<V: Clone> Clone for LruCache<V> {
+    fn clone(&self) -> LruCache<V> {
+        LruCache { state: self.state.clone() }
+    }
+}
+
+impl<V: Clone> LruCache<V> {
+    /// Creates new instance of `LruCache`.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - maximum number of entries, least recently used entries are dropped if exceeded
+    /// * `ttl` - default time to live for entries
+    pub fn new(capacity: usize, ttl: Duration) -> LruCache<V> {
+        LruCache {
+            state: Rc::new(RefCell::new(CacheState {
+                capacity,
+                ttl,
+                entries: HashMap::new(),
+                tick: 0,
+                stats: CacheStats { hits: 0, misses: 0, evictions: 0 },
+            })),
+        }
+    }
+
+    /// Gets value for given key, expired entries are removed and count as miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to look up
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.get_at(key, &Utc::now())
+    }
+
+    /// Like [`get`](LruCache::get), but checks expiry against given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to look up
+    /// * `now` - current time
+    pub fn get_at(&self, key: &str, now: &DateTime<Utc>) -> Option<V> {
+        self.get_matching_at(key, now, |_| true)
+    }
+
+    /// Like [`get_at`](LruCache::get_at), but only returns values accepted by `filter`, other
+    /// values are kept and count as miss.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to look up
+    /// * `now` - current time
+    /// * `filter` - returns `true` for values that may be used
+    pub fn get_matching_at<F>(&self, key: &str, now: &DateTime<Utc>, filter: F) -> Option<V>
+    where
+        F: Fn(&V) -> bool,
+    {
+        let value = self.peek_at(key, now).filter(|value| filter(value));
+        let mut state = self.state.borrow_mut();
+        match value {
+            Some(_) => state.stats.hits += 1,
+            None => state.stats.misses += 1,
+        };
+        value
+    }
+
+    /// Gets value for given key like [`get_at`](LruCache::get_at), but does not count the lookup
+    /// in [`stats`](LruCache::stats).
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to look up
+    /// * `now` - current time
+    pub fn peek_at(&self, key: &str, now: &DateTime<Utc>) -> Option<V> {
+        let mut state = self.state.borrow_mut();
+        state.tick += 1;
+        let tick = state.tick;
+        match state.entries.get_mut(key) {
+            Some(entry) if entry.expires > *now => {
+                entry.last_used = tick;
+                Some(entry.value.clone())
+            },
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            },
+            None => None,
+        }
+    }
+
+    /// Stores value for given key with the default TTL.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to store value under
+    /// * `value` - value to store
+    pub fn set(&self, key: &str, value: V) {
+        self.set_at(key, value, &Utc::now());
+    }
+
+    /// Like [`set`](LruCache::set), but calculates expiry from given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to store value under
+    /// * `value` - value to store
+    /// * `now` - current time
+    pub fn set_at(&self, key: &str, value: V, now: &DateTime<Utc>) {
+        let ttl = self.state.borrow().ttl;
+        self.set_with_ttl_at(key, value, ttl, now);
+    }
+
+    /// Stores value for given key with a custom TTL.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to store value under
+    /// * `value` - value to store
+    /// * `ttl` - time to live for this entry
+    pub fn set_with_ttl(&self, key: &str, value: V, ttl: Duration) {
+        self.set_with_ttl_at(key, value, ttl, &Utc::now());
+    }
+
+    /// Like [`set_with_ttl`](LruCache::set_with_ttl), but calculates expiry from given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to store value under
+    /// * `value` - value to store
+    /// * `ttl` - time to live for this entry
+    /// * `now` - current time
+    pub fn set_with_ttl_at(&self, key: &str, value: V, ttl: Duration, now: &DateTime<Utc>) {
+        let mut state = self.state.borrow_mut();
+        if state.capacity == 0 {
+            return;
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        if !state.entries.contains_key(key) && state.entries.len() >= state.capacity {
+            state.entries.retain(|_, entry| entry.expires > *now);
+            if state.entries.len() >= state.capacity {
+                let oldest = state.entries.iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.to_string());
+                if let Some(oldest) = oldest {
+                    state.entries.remove(&oldest);
+                    state.stats.evictions += 1;
+                }
+            }
+        }
+        state.entries.insert(key.to_string(), CacheEntry {
+            value,
+            expires: *now + ttl,
+            last_used: tick,
+        });
+    }
+
+    /// Removes entry for given key, returns `true` if an entry was removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key to remove
+    pub fn invalidate(&self, key: &str) -> bool {
+        self.state.borrow_mut().entries.remove(key).is_some()
+    }
+
+    /// Removes all entries, stats are kept.
+    pub fn clear(&self) {
+        self.state.borrow_mut().entries.clear();
+    }
+
+    /// Returns number of stored entries, including expired entries that have not been removed yet.
+    pub fn len(&self) -> usize {
+        self.state.borrow().entries.len()
+    }
+
+    /// Returns `true` if no entries are stored.
+    pub fn is_empty(&self) -> bool {
+        self.state.borrow().entries.is_empty()
+    }
+
+    /// Returns hit, miss and eviction counters.
+    pub fn stats(&self) -> CacheStats {
+        self.state.borrow().stats
+    }
+}
@@ -0,0 +1,78 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Time sources for creating and checking VCs.
+
+use chrono::{ DateTime, Duration, Utc };
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Provides the current time.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Uses the system time.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Returns a fixed time that can be changed manually, e.g. for testing. Clones share their time.
+#[derive(Clone, Debug)]
+pub struct FixedClock {
+    now: Rc<Cell<DateTime<Utc>>>,
+}
+
+impl FixedClock {
+    /// Creates new instance of `FixedClock`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - time to return
+    pub fn new(now: DateTime<Utc>) -> FixedClock {
+        FixedClock { now: Rc::new(Cell::new(now)) }
+    }
+
+    /// Sets time to return.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - time to return
+    pub fn set(&self, now: DateTime<Utc>) {
+        self.now.set(now);
+    }
+
+    /// Moves time by given duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - duration to add, may be negative
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.get()
+    }
+}
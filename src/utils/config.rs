@@ -0,0 +1,160 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Configuration for resolvers, that fetch documents from [evan.network](https://evan.network/) nodes.
+
+use std::collections::HashMap;
+use crate::utils::errors::VadeEvanError;
+
+/// base URL of evan.network testnet nodes, used for ids like `did:evan:testcore:0x...`
+pub const EVAN_TESTCORE_ENDPOINT: &str = "https://testcore.evan.network/";
+/// base URL of evan.network mainnet nodes, used for ids like `did:evan:core:0x...`
+pub const EVAN_CORE_ENDPOINT: &str = "https://core.evan.network/";
+/// network used for ids without network segment, e.g. `did:evan:0x...`
+pub const EVAN_DEFAULT_NETWORK: &str = "testcore";
+
+/// Configuration for resolvers, maps network segments of DIDs/VC ids to node base URLs.
+///
+/// Network segments are parsed from ids as follows:
+/// - `did:evan:testcore:0x...` -> `"testcore"`
+/// - `did:evan:mynet:0x...` -> `"mynet"` (custom networks, must be added with [`with_endpoint`])
+/// - `did:evan:0x...` -> `default_network`, which is `"testcore"` unless changed with
+///   [`with_default_network`], e.g. to `"core"` to resolve these ids on mainnet
+///
+///
+/// Documents are written to separate write endpoints (agents), that have to be added with
+/// [`with_write_endpoint`], no write endpoints are configured by default.
+///
+/// [`with_default_network`]: ResolverConfig::with_default_network
+/// [`with_endpoint`]: ResolverConfig::with_endpoint
+/// [`with_write_endpoint`]: ResolverConfig::with_write_endpoint
+#[derive(Clone, Debug)]
+pub struct ResolverConfig {
+    /// base URLs of nodes per network segment
+    pub endpoints: HashMap<String, String>,
+    /// base URLs of agents documents are written to per network segment
+    pub write_endpoints: HashMap<String, String>,
+    /// network used for ids without network segment
+    pub default_network: String,
+}
+
+impl ResolverConfig {
+    /// Creates new config with endpoints for `testcore` and `core`, ids without network segment
+    /// are resolved on `testcore`.
+    pub fn new() -> ResolverConfig {
+        ResolverConfig::empty()
+            .with_endpoint("testcore", EVAN_TESTCORE_ENDPOINT)
+            .with_endpoint("core", EVAN_CORE_ENDPOINT)
+    }
+
+    /// Creates new config without any endpoints.
+    pub fn empty() -> ResolverConfig {
+        ResolverConfig {
+            endpoints: HashMap::new(),
+            write_endpoints: HashMap::new(),
+            default_network: EVAN_DEFAULT_NETWORK.to_string(),
+        }
+    }
+
+    /// Sets network used for ids without network segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - network segment, e.g. `"core"`
+    pub fn with_default_network(mut self, network: &str) -> ResolverConfig {
+        self.default_network = network.to_string();
+        self
+    }
+
+    /// Adds or replaces endpoint for given network segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - network segment, e.g. `"testcore"`
+    /// * `base_url` - base URL of node, e.g. `"http://localhost:8080/"`
+    pub fn with_endpoint(mut self, network: &str, base_url: &str) -> ResolverConfig {
+        self.endpoints.insert(network.to_string(), base_url.to_string());
+        self
+    }
+
+    /// Adds or replaces endpoint documents are written to for given network segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - network segment, e.g. `"testcore"`
+    /// * `base_url` - base URL of agent, e.g. `"http://localhost:8080/"`
+    pub fn with_write_endpoint(mut self, network: &str, base_url: &str) -> ResolverConfig {
+        self.write_endpoints.insert(network.to_string(), base_url.to_string());
+        self
+    }
+
+    /// Gets network segment from a DID or VC id, returns `None` for ids without network segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - DID or VC id, e.g. `"did:evan:testcore:0x..."`
+    pub fn get_network(id: &str) -> Result<Option<String>, VadeEvanError> {
+        let split: Vec<&str> = id.split(':').collect();
+        match split.as_slice() {
+            ["did", "evan", _] | ["vc", "evan", _] => Ok(None),
+            ["did", "evan", network, _] | ["vc", "evan", network, _] => Ok(Some(network.to_string())),
+            _ => Err(VadeEvanError::Parse(format!("could not parse network from id \"{}\"", id))),
+        }
+    }
+
+    /// Gets URL for given document type and id.
+    ///
+    /// # Arguments
+    ///
+    /// * `document_type` - type of document, used as path segment, e.g. `"did"` or `"vc"`
+    /// * `id` - DID or VC id, e.g. `"did:evan:testcore:0x..."`
+    pub fn get_url(&self, document_type: &str, id: &str) -> Result<String, VadeEvanError> {
+        let network = ResolverConfig::get_network(id)?.unwrap_or_else(|| self.default_network.to_string());
+        Ok(format!("{}/{}/{}", self.get_endpoint(&network)?, document_type, id))
+    }
+
+    /// Gets URL documents of given type and id are written to.
+    ///
+    /// # Arguments
+    ///
+    /// * `document_type` - type of document, used as path segment, e.g. `"did"`
+    /// * `id` - DID or VC id, e.g. `"did:evan:testcore:0x..."`
+    pub fn get_write_url(&self, document_type: &str, id: &str) -> Result<String, VadeEvanError> {
+        let network = ResolverConfig::get_network(id)?.unwrap_or_else(|| self.default_network.to_string());
+        match self.write_endpoints.get(&network) {
+            Some(base_url) => Ok(format!("{}/{}/{}", base_url.trim_end_matches('/'), document_type, id)),
+            None => Err(VadeEvanError::Config(format!("no write endpoint configured for network \"{}\"", network))),
+        }
+    }
+
+    /// Gets base URL for given network without trailing slash.
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - network segment, e.g. `"testcore"`
+    pub fn get_endpoint(&self, network: &str) -> Result<&str, VadeEvanError> {
+        match self.endpoints.get(network) {
+            Some(base_url) => Ok(base_url.trim_end_matches('/')),
+            None => Err(VadeEvanError::Config(format!("no endpoint configured for network \"{}\"", network))),
+        }
+    }
+}
+
+impl Default for ResolverConfig {
+    fn default() -> ResolverConfig {
+        ResolverConfig::new()
+    }
+}
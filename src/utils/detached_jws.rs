@@ -0,0 +1,199 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Creation and verification of proofs with detached JWS ([RFC 7797]), e.g. `Ed25519Signature2018`
+//! and `JsonWebSignature2020`.
+//!
+//! The signed payload is built like for Linked Data proofs, from the SHA-256 hashes of the proof
+//! options (proof without `jws`) and the document (without `proof`). Both are canonicalized with
+//! JCS ([RFC 8785]) instead of RDF dataset normalization, so proofs can only be verified by
+//! implementations using the same canonicalization.
+//!
+//! [RFC 7797]: https://tools.ietf.org/html/rfc7797
+//! [RFC 8785]: https://tools.ietf.org/html/rfc8785
+
+use chrono::{ DateTime, Utc };
+use data_encoding::BASE64URL_NOPAD;
+use ed25519_dalek::Signature;
+use serde_json::Value;
+use sha2::{ Digest, Sha256 };
+use std::convert::TryFrom;
+use crate::utils::errors::VadeEvanError;
+use crate::utils::signer::{ Ed25519Signer, Signer };
+use crate::utils::signing::{ canonicalize_json, decode_base64url, sign_with_signer, verify_signature, JWS_ALG_ES256K };
+use crate::utils::verification_key::VerificationKey;
+
+/// proof type for Ed25519 signatures
+pub const PROOF_TYPE_ED25519_SIGNATURE_2018: &str = "Ed25519Signature2018";
+/// proof type for JWS with any supported algorithm, `ES256K` and `EdDSA` are supported
+pub const PROOF_TYPE_JSON_WEB_SIGNATURE_2020: &str = "JsonWebSignature2020";
+/// JWS algorithm of Ed25519 signatures
+pub const JWS_ALG_EDDSA: &str = "EdDSA";
+
+/// Creates `Ed25519Signature2018` proof for a document.
+///
+/// # Arguments
+///
+/// * `document` - document to create proof for, an existing `proof` is ignored
+/// * `signer` - signer to create proof with, its key id is used as `verificationMethod`
+/// * `challenge` - challenge of a verifier to add to proof, if any
+/// * `now` - timestamp of issuing
+pub async fn create_ed25519_proof(
+    document: &Value,
+    signer: &dyn Ed25519Signer,
+    challenge: Option<&str>,
+    now: &DateTime<Utc>,
+) -> Result<Value, VadeEvanError> {
+    let mut proof = create_proof_options(PROOF_TYPE_ED25519_SIGNATURE_2018, &signer.key_id(), challenge, now);
+    let header = encode_header(JWS_ALG_EDDSA)?;
+    let signing_input = create_signing_input(&header, document, &proof)?;
+    let signature = signer.sign(&signing_input).await?;
+    proof["jws"] = Value::from(format!("{}..{}", header, BASE64URL_NOPAD.encode(&signature)));
+
+    Ok(proof)
+}
+
+/// Creates `JsonWebSignature2020` proof for a document, signed with `ES256K`.
+///
+/// # Arguments
+///
+/// * `document` - document to create proof for, an existing `proof` is ignored
+/// * `signer` - signer to create proof with, its key id is used as `verificationMethod`
+/// * `challenge` - challenge of a verifier to add to proof, if any
+/// * `now` - timestamp of issuing
+pub async fn create_json_web_signature_proof(
+    document: &Value,
+    signer: &dyn Signer,
+    challenge: Option<&str>,
+    now: &DateTime<Utc>,
+) -> Result<Value, VadeEvanError> {
+    let mut proof = create_proof_options(PROOF_TYPE_JSON_WEB_SIGNATURE_2020, &signer.key_id(), challenge, now);
+    let header = encode_header(JWS_ALG_ES256K)?;
+    let signing_input = create_signing_input(&header, document, &proof)?;
+    let signature = sign_with_signer(&signing_input, signer).await?;
+    // ES256K signatures omit the recovery id
+    proof["jws"] = Value::from(format!("{}..{}", header, BASE64URL_NOPAD.encode(&signature[..64])));
+
+    Ok(proof)
+}
+
+/// Verifies detached JWS of a proof with given key.
+///
+/// # Arguments
+///
+/// * `document` - signed document, an existing `proof` is ignored
+/// * `proof` - proof to verify
+/// * `key` - key of `verificationMethod`
+pub fn verify_detached_jws_proof(document: &Value, proof: &Value, key: &VerificationKey) -> Result<(), VadeEvanError> {
+    let jws = proof["jws"].as_str()
+        .ok_or_else(|| VadeEvanError::Schema("proof has no \"jws\"".to_string()))?;
+    let split: Vec<&str> = jws.split('.').collect();
+    if split.len() != 3 || !split[1].is_empty() {
+        return Err(VadeEvanError::Parse("jws is not a detached JWS".to_string()));
+    }
+    let header: Value = serde_json::from_slice(&decode_base64url(split[0])?)?;
+    if header["b64"] != false || !matches!(header["crit"].as_array(), Some(crit) if crit.iter().any(|v| v == "b64")) {
+        return Err(VadeEvanError::Parse("jws header has to contain \"b64\": false and list it in \"crit\"".to_string()));
+    }
+    let signature = decode_base64url(split[2])?;
+    let signing_input = create_signing_input(split[0], document, proof)?;
+
+    let alg = header["alg"].as_str();
+    if proof["type"] == PROOF_TYPE_ED25519_SIGNATURE_2018 && alg != Some(JWS_ALG_EDDSA) {
+        return Err(VadeEvanError::Signature(format!("{} proofs have to use alg \"{}\"", PROOF_TYPE_ED25519_SIGNATURE_2018, JWS_ALG_EDDSA)));
+    }
+    match (alg, key) {
+        (Some(JWS_ALG_EDDSA), VerificationKey::Ed25519(public_key)) => {
+            let signature = Signature::try_from(&signature[..])
+                .map_err(|e| VadeEvanError::Signature(format!("invalid signature, {}", e)))?;
+            // strict verification rejects malleable signatures and weak keys
+            public_key.verify_strict(&signing_input, &signature)
+                .map_err(|_| VadeEvanError::Signature("signature does not match key".to_string()))
+        },
+        (Some(JWS_ALG_ES256K), _) => verify_signature(&signing_input, &signature, key),
+        (Some(alg), _) => Err(VadeEvanError::Signature(format!("key cannot be used for jws with alg \"{}\"", alg))),
+        (None, _) => Err(VadeEvanError::Parse("jws header has no \"alg\"".to_string())),
+    }
+}
+
+/// Creates proof options, i.e. proof without `jws`.
+///
+/// # Arguments
+///
+/// * `proof_type` - `type` of proof
+/// * `verification_method` - key to sign with
+/// * `challenge` - challenge of a verifier, added as `challenge` if given
+/// * `now` - timestamp of issuing
+fn create_proof_options(
+    proof_type: &str,
+    verification_method: &str,
+    challenge: Option<&str>,
+    now: &DateTime<Utc>,
+) -> Value {
+    let mut proof = serde_json::json!({
+        "type": proof_type,
+        "created": format!("{}", now.format("%Y-%m-%dT%H:%M:%S.000Z")),
+        "proofPurpose": "assertionMethod",
+        "verificationMethod": verification_method,
+    });
+    if let Some(challenge) = challenge {
+        proof["challenge"] = Value::from(challenge);
+    }
+
+    proof
+}
+
+/// Encodes JWS header for a detached, unencoded payload.
+///
+/// # Arguments
+///
+/// * `alg` - signature algorithm, e.g. `"EdDSA"`
+fn encode_header(alg: &str) -> Result<String, VadeEvanError> {
+    let header = serde_json::json!({ "alg": alg, "b64": false, "crit": [ "b64" ] });
+    Ok(BASE64URL_NOPAD.encode(serde_json::to_string(&header)?.as_bytes()))
+}
+
+/// Creates JWS signing input `<header>.<payload>` with the unencoded payload
+/// `SHA-256(JCS(proof options)) || SHA-256(JCS(document))`.
+///
+/// # Arguments
+///
+/// * `header` - base64url encoded JWS header
+/// * `document` - signed document, an existing `proof` is ignored
+/// * `proof` - proof, an existing `jws` is ignored
+fn create_signing_input(header: &str, document: &Value, proof: &Value) -> Result<Vec<u8>, VadeEvanError> {
+    let document = without_property(document, "proof")?;
+    let proof_options = without_property(proof, "jws")?;
+    let mut signing_input = format!("{}.", header).into_bytes();
+    signing_input.extend(Sha256::digest(canonicalize_json(&proof_options)?.as_bytes()));
+    signing_input.extend(Sha256::digest(canonicalize_json(&document)?.as_bytes()));
+
+    Ok(signing_input)
+}
+
+/// Returns copy of given object without given property.
+///
+/// # Arguments
+///
+/// * `value` - object to copy
+/// * `property` - property to remove
+fn without_property(value: &Value, property: &str) -> Result<Value, VadeEvanError> {
+    let mut value = value.clone();
+    value.as_object_mut()
+        .ok_or_else(|| VadeEvanError::Schema("signed documents and proofs have to be objects".to_string()))?
+        .remove(property);
+    Ok(value)
+}
@@ -0,0 +1,110 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Error type returned by the plugins of this crate.
+//!
+//! Trait implementations for [`vade`] return `Box<dyn std::error::Error>`, errors in these boxes are
+//! always [`VadeEvanError`]s and can be retrieved with `downcast_ref::<VadeEvanError>()` when calling
+//! the trait implementations directly. `Vade` wraps errors of its plugins in errors of its own, so
+//! errors returned by `Vade` cannot be downcasted.
+//!
+//! [`vade`]: https://docs.rs/vade
+
+use std::error::Error;
+use std::fmt;
+
+/// Errors of DID and VC handling, each variant holds a message describing the error.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VadeEvanError {
+    /// input, JWS or response could not be parsed
+    Parse(String),
+    /// signature could not be created, recovered or does not match expected key
+    Signature(String),
+    /// key could not be found in DID document
+    KeyNotFound(String),
+    /// request to a node failed or node answered with an error
+    Network(String),
+    /// VC is not active anymore
+    Revoked(String),
+    /// VC is expired or not valid yet
+    Validity(String),
+    /// document does not have expected structure
+    Schema(String),
+    /// resolver is not configured for requested operation
+    Config(String),
+    /// VC does not satisfy the verification policy
+    Policy(String),
+    /// presentation is not bound to the expected challenge or domain
+    Challenge(String),
+    /// resolver is not responsible for given id or does not support requested feature
+    Unsupported(String),
+}
+
+impl fmt::Display for VadeEvanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VadeEvanError::Parse(message)
+                | VadeEvanError::Signature(message)
+                | VadeEvanError::KeyNotFound(message)
+                | VadeEvanError::Network(message)
+                | VadeEvanError::Revoked(message)
+                | VadeEvanError::Validity(message)
+                | VadeEvanError::Schema(message)
+                | VadeEvanError::Config(message)
+                | VadeEvanError::Policy(message)
+                | VadeEvanError::Challenge(message)
+                | VadeEvanError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for VadeEvanError {}
+
+impl From<serde_json::Error> for VadeEvanError {
+    fn from(error: serde_json::Error) -> VadeEvanError {
+        VadeEvanError::Parse(format!("could not parse JSON, {}", error))
+    }
+}
+
+impl From<reqwest::Error> for VadeEvanError {
+    fn from(error: reqwest::Error) -> VadeEvanError {
+        VadeEvanError::Network(format!("{}", error))
+    }
+}
+
+impl From<data_encoding::DecodeError> for VadeEvanError {
+    fn from(error: data_encoding::DecodeError) -> VadeEvanError {
+        VadeEvanError::Parse(format!("could not decode base64, {}", error))
+    }
+}
+
+impl From<std::string::FromUtf8Error> for VadeEvanError {
+    fn from(error: std::string::FromUtf8Error) -> VadeEvanError {
+        VadeEvanError::Parse(format!("could not decode utf8, {}", error))
+    }
+}
+
+impl From<regex::Error> for VadeEvanError {
+    fn from(error: regex::Error) -> VadeEvanError {
+        VadeEvanError::Config(format!("invalid regex, {}", error))
+    }
+}
+
+impl From<secp256k1::Error> for VadeEvanError {
+    fn from(error: secp256k1::Error) -> VadeEvanError {
+        VadeEvanError::Signature(format!("secp256k1 error, {}", error))
+    }
+}
@@ -0,0 +1,35 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Helpers shared by the [`plugin`] implementations, e.g. configuration for the resolvers.
+//!
+//! [`plugin`]: crate::plugin
+
+pub mod cache;
+pub mod clock;
+pub mod config;
+pub mod detached_jws;
+pub mod errors;
+pub mod nonce_store;
+pub mod signer;
+pub mod signing;
+pub mod status_cache;
+pub mod transport;
+pub mod trusted_issuers;
+pub mod vc_storage;
+pub mod verification_key;
+pub mod verification_policy;
+pub mod verification_report;
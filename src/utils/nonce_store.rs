@@ -0,0 +1,153 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Stores for challenges of proofs, used by [`RustVcResolverEvan`] to reject replayed proofs.
+//!
+//! [`RustVcResolverEvan`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan
+
+use chrono::{ DateTime, Duration, Utc };
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use crate::utils::errors::VadeEvanError;
+
+/// Remembers nonces, e.g. challenges of proofs, so each of them can only be used once.
+pub trait NonceStore {
+    /// Marks nonce as used, returns `false` if it has already been used before and has not expired yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - nonce to use
+    /// * `now` - current time, nonces expire relative to it
+    fn use_nonce(&self, nonce: &str, now: &DateTime<Utc>) -> Result<bool, VadeEvanError>;
+
+    /// Gets duration after which used nonces are forgotten and could be used again, proofs older
+    /// than this are rejected.
+    fn ttl(&self) -> Duration;
+}
+
+/// Keeps used nonces in memory until they expire.
+pub struct InMemoryNonceStore {
+    /// duration after which used nonces are forgotten
+    pub ttl: Duration,
+    nonces: RefCell<HashMap<String, DateTime<Utc>>>,
+}
+
+impl InMemoryNonceStore {
+    /// Creates new instance of `InMemoryNonceStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - duration after which used nonces are forgotten
+    pub fn new(ttl: Duration) -> InMemoryNonceStore {
+        InMemoryNonceStore {
+            ttl,
+            nonces: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl NonceStore for InMemoryNonceStore {
+    fn use_nonce(&self, nonce: &str, now: &DateTime<Utc>) -> Result<bool, VadeEvanError> {
+        let mut nonces = self.nonces.borrow_mut();
+        nonces.retain(|_, expires| *expires > *now);
+        if nonces.contains_key(nonce) {
+            return Ok(false);
+        }
+        nonces.insert(nonce.to_string(), *now + self.ttl);
+        Ok(true)
+    }
+
+    fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+/// Keeps used nonces in a JSON file until they expire, so they are remembered across restarts.
+/// The file maps nonces to their expiry and is rewritten on every use through a temporary file
+/// next to it, which replaces the file, so it is not left truncated if writing fails. It must not
+/// be shared by multiple processes.
+pub struct FileNonceStore {
+    /// path of JSON file, created on first use
+    pub path: String,
+    /// duration after which used nonces are forgotten
+    pub ttl: Duration,
+}
+
+impl FileNonceStore {
+    /// Creates new instance of `FileNonceStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path of JSON file, created on first use
+    /// * `ttl` - duration after which used nonces are forgotten
+    pub fn new(path: &str, ttl: Duration) -> FileNonceStore {
+        FileNonceStore { path: path.to_string(), ttl }
+    }
+
+    /// Reads nonces and their expiry from file, returns no nonces if file does not exist yet.
+    fn read(&self) -> Result<HashMap<String, DateTime<Utc>>, VadeEvanError> {
+        let json = match std::fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(VadeEvanError::Config(format!("could not read nonce store \"{}\", {}", self.path, e))),
+        };
+        let parsed: Value = serde_json::from_str(&json)?;
+        let entries = parsed.as_object()
+            .ok_or_else(|| VadeEvanError::Schema(format!("nonce store \"{}\" is not an object", self.path)))?;
+        let mut nonces = HashMap::new();
+        for (nonce, expires) in entries {
+            let expires = expires.as_str()
+                .and_then(|expires| DateTime::parse_from_rfc3339(expires).ok())
+                .ok_or_else(|| VadeEvanError::Schema(format!("nonce store \"{}\" has an invalid expiry", self.path)))?;
+            nonces.insert(nonce.to_string(), expires.with_timezone(&Utc));
+        }
+        Ok(nonces)
+    }
+
+    /// Writes nonces and their expiry to a temporary file and replaces file with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonces` - nonces to write
+    fn write(&self, nonces: &HashMap<String, DateTime<Utc>>) -> Result<(), VadeEvanError> {
+        let mut entries = serde_json::Map::new();
+        for (nonce, expires) in nonces {
+            entries.insert(nonce.to_string(), Value::from(expires.to_rfc3339()));
+        }
+        let tmp_path = format!("{}.tmp", self.path);
+        std::fs::write(&tmp_path, Value::from(entries).to_string())
+            .and_then(|_| std::fs::rename(&tmp_path, &self.path))
+            .map_err(|e| VadeEvanError::Config(format!("could not write nonce store \"{}\", {}", self.path, e)))
+    }
+}
+
+impl NonceStore for FileNonceStore {
+    fn use_nonce(&self, nonce: &str, now: &DateTime<Utc>) -> Result<bool, VadeEvanError> {
+        let mut nonces = self.read()?;
+        nonces.retain(|_, expires| *expires > *now);
+        if nonces.contains_key(nonce) {
+            return Ok(false);
+        }
+        nonces.insert(nonce.to_string(), *now + self.ttl);
+        self.write(&nonces)?;
+        Ok(true)
+    }
+
+    fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
@@ -0,0 +1,431 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Signers holding the keys used to create proofs, so keys do not have to be passed around as
+//! strings.
+//!
+//! [`InMemorySigner`] keeps a key in memory, [`KeystoreSigner`] loads it from a password encrypted
+//! Ethereum keystore file. Keys kept elsewhere, e.g. in HSMs or remote signing services, can be
+//! used by implementing [`Signer`] for them. Ed25519 keys are held by [`Ed25519Signer`]s like
+//! [`InMemoryEd25519Signer`].
+
+use aes::Aes128;
+use async_trait::async_trait;
+use ctr::Ctr128BE;
+use ctr::cipher::{ KeyIvInit, StreamCipher };
+use ed25519_dalek::{ Keypair, PublicKey as Ed25519PublicKey, SecretKey as Ed25519SecretKey, Signer as _ };
+use rand::RngCore;
+use rand::rngs::OsRng;
+use secp256k1::{ Message, PublicKey, SecretKey, sign };
+use serde_json::Value;
+use sha2::Sha256;
+use sha3::{ Digest, Keccak256 };
+use std::convert::TryFrom;
+use zeroize::Zeroizing;
+use crate::utils::errors::VadeEvanError;
+use crate::utils::signing::get_ethereum_address;
+
+/// keystore format version written and read by [`KeystoreSigner`]
+pub const KEYSTORE_VERSION: u64 = 3;
+/// default scrypt cost parameter for new keystores as log2, i.e. `n` = 262144
+pub const DEFAULT_KEYSTORE_SCRYPT_LOG_N: u8 = 18;
+/// scrypt block size parameter `r` for new keystores
+const KEYSTORE_SCRYPT_R: u32 = 8;
+/// scrypt parallelization parameter `p` for new keystores
+const KEYSTORE_SCRYPT_P: u32 = 1;
+/// length of keys derived from keystore passwords
+const KEYSTORE_DKLEN: usize = 32;
+
+/// Creates secp256k1 signatures, e.g. for `EcdsaPublicKeySecp256k1` and `JsonWebSignature2020` proofs.
+#[async_trait(?Send)]
+pub trait Signer {
+    /// Returns id of signing key, used as `verificationMethod` of proofs, e.g.
+    /// `"did:evan:testcore:0x...#key-1"`.
+    fn key_id(&self) -> String;
+
+    /// Returns public key of signing key.
+    async fn public_key(&self) -> Result<PublicKey, VadeEvanError>;
+
+    /// Returns `0x` prefixed Ethereum address of signing key in lower case.
+    async fn address(&self) -> Result<String, VadeEvanError> {
+        Ok(format!("0x{}", get_ethereum_address(&self.public_key().await?)))
+    }
+
+    /// Signs a 32B digest, returns 64B signature with appended recovery id.
+    ///
+    /// # Arguments
+    ///
+    /// * `digest` - digest to sign, e.g. SHA-256 hash of a message
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<[u8; 65], VadeEvanError>;
+}
+
+/// Signer whose private key is held in memory and can be exported, e.g. to store it with
+/// [`KeystoreSigner::save`]. Signers keeping keys elsewhere, e.g. in HSMs, do not implement this.
+pub trait ExportableSigner: Signer {
+    /// Returns 32B private key, overwritten with zeros when dropped.
+    fn export_private_key(&self) -> Zeroizing<[u8; 32]>;
+}
+
+/// Creates Ed25519 signatures, e.g. for `Ed25519Signature2018` proofs.
+#[async_trait(?Send)]
+pub trait Ed25519Signer {
+    /// Returns id of signing key, used as `verificationMethod` of proofs, e.g.
+    /// `"did:evan:testcore:0x...#key-1"`.
+    fn key_id(&self) -> String;
+
+    /// Returns public key of signing key.
+    async fn public_key(&self) -> Result<Ed25519PublicKey, VadeEvanError>;
+
+    /// Signs a message, returns 64B signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - message to sign
+    async fn sign(&self, message: &[u8]) -> Result<[u8; 64], VadeEvanError>;
+}
+
+/// Keeps a secp256k1 key in memory. The key is overwritten with zeros when the signer is dropped.
+pub struct InMemorySigner {
+    key_id: String,
+    secret_key: SecretKey,
+}
+
+impl InMemorySigner {
+    /// Creates new instance of `InMemorySigner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    /// * `private_key` - 32B private key
+    pub fn new(key_id: &str, private_key: &[u8]) -> Result<InMemorySigner, VadeEvanError> {
+        let mut private_key_arr = Zeroizing::new([0u8; 32]);
+        if private_key.len() != 32 {
+            return Err(VadeEvanError::Signature("private key invalid".to_string()));
+        }
+        private_key_arr.copy_from_slice(private_key);
+        Ok(InMemorySigner {
+            key_id: key_id.to_string(),
+            secret_key: SecretKey::parse(&private_key_arr)?,
+        })
+    }
+
+    /// Creates new instance of `InMemorySigner` from a private key given as hex string.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    /// * `private_key` - private key as 32B hex string
+    pub fn from_hex(key_id: &str, private_key: &str) -> Result<InMemorySigner, VadeEvanError> {
+        let private_key = Zeroizing::new(hex::decode(private_key)
+            .map_err(|_| VadeEvanError::Signature("private key invalid".to_string()))?);
+        InMemorySigner::new(key_id, &private_key)
+    }
+
+    /// Creates new instance of `InMemorySigner` with a random key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    pub fn generate(key_id: &str) -> InMemorySigner {
+        InMemorySigner {
+            key_id: key_id.to_string(),
+            secret_key: SecretKey::random(&mut OsRng),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Signer for InMemorySigner {
+    fn key_id(&self) -> String {
+        self.key_id.to_string()
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, VadeEvanError> {
+        Ok(PublicKey::from_secret_key(&self.secret_key))
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<[u8; 65], VadeEvanError> {
+        let (signature, recovery_id) = sign(&Message::parse(digest), &self.secret_key);
+        let mut sig_and_rec = [0u8; 65];
+        sig_and_rec[..64].copy_from_slice(&signature.serialize());
+        sig_and_rec[64] = recovery_id.serialize();
+        Ok(sig_and_rec)
+    }
+}
+
+impl ExportableSigner for InMemorySigner {
+    fn export_private_key(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.secret_key.serialize())
+    }
+}
+
+/// Keeps an Ed25519 key in memory. The key is overwritten with zeros when the signer is dropped.
+pub struct InMemoryEd25519Signer {
+    key_id: String,
+    keypair: Keypair,
+}
+
+impl InMemoryEd25519Signer {
+    /// Creates new instance of `InMemoryEd25519Signer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    /// * `private_key` - 32B private key (seed)
+    pub fn new(key_id: &str, private_key: &[u8]) -> Result<InMemoryEd25519Signer, VadeEvanError> {
+        let secret = Ed25519SecretKey::from_bytes(private_key)
+            .map_err(|e| VadeEvanError::Signature(format!("private key invalid, {}", e)))?;
+        let public = Ed25519PublicKey::from(&secret);
+        Ok(InMemoryEd25519Signer {
+            key_id: key_id.to_string(),
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    /// Creates new instance of `InMemoryEd25519Signer` from a private key given as hex string.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    /// * `private_key` - private key (seed) as 32B hex string
+    pub fn from_hex(key_id: &str, private_key: &str) -> Result<InMemoryEd25519Signer, VadeEvanError> {
+        let private_key = Zeroizing::new(hex::decode(private_key)
+            .map_err(|_| VadeEvanError::Signature("private key invalid".to_string()))?);
+        InMemoryEd25519Signer::new(key_id, &private_key)
+    }
+
+    /// Creates new instance of `InMemoryEd25519Signer` with a random key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    pub fn generate(key_id: &str) -> InMemoryEd25519Signer {
+        let secret = Ed25519SecretKey::generate(&mut OsRng);
+        let public = Ed25519PublicKey::from(&secret);
+        InMemoryEd25519Signer {
+            key_id: key_id.to_string(),
+            keypair: Keypair { secret, public },
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl Ed25519Signer for InMemoryEd25519Signer {
+    fn key_id(&self) -> String {
+        self.key_id.to_string()
+    }
+
+    async fn public_key(&self) -> Result<Ed25519PublicKey, VadeEvanError> {
+        Ok(self.keypair.public)
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<[u8; 64], VadeEvanError> {
+        Ok(self.keypair.sign(message).to_bytes())
+    }
+}
+
+/// Loads a secp256k1 key from a password encrypted keystore file.
+///
+/// Keystores use the [Web3 Secret Storage] format (version 3) of Ethereum clients, so keystores
+/// can be exchanged with them. The key is derived from the password with scrypt or
+/// PBKDF2-HMAC-SHA256, the private key is encrypted with AES-128-CTR and authenticated with a
+/// Keccak-256 MAC. New keystores are written with scrypt.
+///
+/// [Web3 Secret Storage]: https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
+pub struct KeystoreSigner {
+    signer: InMemorySigner,
+}
+
+impl KeystoreSigner {
+    /// Decrypts keystore file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path of keystore file
+    /// * `key_id` - id of key, e.g. `"did:evan:testcore:0x...#key-1"`
+    /// * `password` - password of keystore
+    pub fn open(path: &str, key_id: &str, password: &str) -> Result<KeystoreSigner, VadeEvanError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| VadeEvanError::Config(format!("could not read keystore \"{}\", {}", path, e)))?;
+        let keystore: Value = serde_json::from_str(&json)?;
+        if keystore["version"] != KEYSTORE_VERSION {
+            return Err(VadeEvanError::Unsupported(format!(
+                "keystore \"{}\" has unsupported version {}", path, keystore["version"])));
+        }
+        let invalid = |property: &str| VadeEvanError::Schema(format!(
+            "keystore \"{}\" has an invalid \"{}\"", path, property));
+        let unsupported = |property: &str, value: &Value| VadeEvanError::Unsupported(format!(
+            "keystore \"{}\" has unsupported \"{}\" {}", path, property, value));
+        let crypto = &keystore["crypto"];
+        let hex_property = |value: &Value, property: &str| value.as_str()
+            .and_then(|value| hex::decode(value).ok())
+            .ok_or_else(|| invalid(property));
+        let u32_property = |value: &Value, property: &str| value.as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .filter(|value| *value > 0)
+            .ok_or_else(|| invalid(property));
+
+        let kdf_params = &crypto["kdfparams"];
+        if kdf_params["dklen"] != KEYSTORE_DKLEN as u64 {
+            return Err(unsupported("dklen", &kdf_params["dklen"]));
+        }
+        let salt = hex_property(&kdf_params["salt"], "salt")?;
+        let mut derived_key = Zeroizing::new([0u8; KEYSTORE_DKLEN]);
+        match crypto["kdf"].as_str() {
+            Some("scrypt") => {
+                let n = u32_property(&kdf_params["n"], "n")?;
+                if !n.is_power_of_two() || n < 2 {
+                    return Err(invalid("n"));
+                }
+                let params = scrypt::Params::new(
+                    n.trailing_zeros() as u8,
+                    u32_property(&kdf_params["r"], "r")?,
+                    u32_property(&kdf_params["p"], "p")?,
+                    KEYSTORE_DKLEN,
+                ).map_err(|_| invalid("kdfparams"))?;
+                scrypt::scrypt(password.as_bytes(), &salt, &params, &mut derived_key[..])
+                    .map_err(|_| invalid("kdfparams"))?;
+            },
+            Some("pbkdf2") => {
+                if kdf_params["prf"] != "hmac-sha256" {
+                    return Err(unsupported("prf", &kdf_params["prf"]));
+                }
+                let c = u32_property(&kdf_params["c"], "c")?;
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, c, &mut derived_key[..]);
+            },
+            _ => return Err(unsupported("kdf", &crypto["kdf"])),
+        }
+
+        if crypto["cipher"] != "aes-128-ctr" {
+            return Err(unsupported("cipher", &crypto["cipher"]));
+        }
+        let iv = hex_property(&crypto["cipherparams"]["iv"], "iv")?;
+        let ciphertext = hex_property(&crypto["ciphertext"], "ciphertext")?;
+        let mac = hex_property(&crypto["mac"], "mac")?;
+        if mac != create_mac(&derived_key, &ciphertext) {
+            return Err(VadeEvanError::Signature(format!("could not decrypt keystore \"{}\", wrong password", path)));
+        }
+        let mut private_key = Zeroizing::new(ciphertext);
+        apply_keystream(&derived_key, &iv, &mut private_key).map_err(|_| invalid("iv"))?;
+        let signer = InMemorySigner::new(key_id, &private_key)?;
+        if let Some(address) = keystore["address"].as_str() {
+            let expected = get_ethereum_address(&PublicKey::from_secret_key(&signer.secret_key));
+            if address.trim_start_matches("0x").to_lowercase() != expected {
+                return Err(invalid("address"));
+            }
+        }
+
+        Ok(KeystoreSigner { signer })
+    }
+
+    /// Encrypts key of given signer and writes it to a keystore file. The key id of `signer` is
+    /// not stored and has to be passed to [`open`](KeystoreSigner::open) again.
+    ///
+    /// Keystores hold secp256k1 keys only, so keys of [`Ed25519Signer`]s cannot be stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path of keystore file, an existing file is overwritten
+    /// * `password` - password to encrypt key with
+    /// * `signer` - signer holding key to store, e.g. an [`InMemorySigner`] or an opened `KeystoreSigner`
+    /// * `scrypt_log_n` - scrypt cost parameter as log2, e.g. [`DEFAULT_KEYSTORE_SCRYPT_LOG_N`]
+    pub fn save(path: &str, password: &str, signer: &dyn ExportableSigner, scrypt_log_n: u8) -> Result<(), VadeEvanError> {
+        let mut salt = [0u8; 32];
+        let mut iv = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut iv);
+        let params = scrypt::Params::new(scrypt_log_n, KEYSTORE_SCRYPT_R, KEYSTORE_SCRYPT_P, KEYSTORE_DKLEN)
+            .map_err(|_| VadeEvanError::Config(format!("invalid scrypt cost parameter {}", scrypt_log_n)))?;
+        let mut derived_key = Zeroizing::new([0u8; KEYSTORE_DKLEN]);
+        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut derived_key[..])
+            .map_err(|_| VadeEvanError::Config(format!("invalid scrypt cost parameter {}", scrypt_log_n)))?;
+        let private_key = signer.export_private_key();
+        let public_key = PublicKey::from_secret_key(&SecretKey::parse(&private_key)?);
+        // encrypted in place, so the copy of the private key is overwritten
+        let mut ciphertext = *private_key;
+        apply_keystream(&derived_key, &iv, &mut ciphertext)?;
+
+        let keystore = serde_json::json!({
+            "version": KEYSTORE_VERSION,
+            "id": uuid::Uuid::new_v4().to_string(),
+            "address": get_ethereum_address(&public_key),
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": { "iv": hex::encode(iv) },
+                "ciphertext": hex::encode(ciphertext),
+                "kdf": "scrypt",
+                "kdfparams": {
+                    "dklen": KEYSTORE_DKLEN,
+                    "n": 1u64 << scrypt_log_n,
+                    "r": KEYSTORE_SCRYPT_R,
+                    "p": KEYSTORE_SCRYPT_P,
+                    "salt": hex::encode(salt),
+                },
+                "mac": hex::encode(create_mac(&derived_key, &ciphertext)),
+            },
+        });
+        std::fs::write(path, keystore.to_string())
+            .map_err(|e| VadeEvanError::Config(format!("could not write keystore \"{}\", {}", path, e)))
+    }
+}
+
+impl ExportableSigner for KeystoreSigner {
+    fn export_private_key(&self) -> Zeroizing<[u8; 32]> {
+        self.signer.export_private_key()
+    }
+}
+
+#[async_trait(?Send)]
+impl Signer for KeystoreSigner {
+    fn key_id(&self) -> String {
+        self.signer.key_id()
+    }
+
+    async fn public_key(&self) -> Result<PublicKey, VadeEvanError> {
+        self.signer.public_key().await
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<[u8; 65], VadeEvanError> {
+        self.signer.sign_digest(digest).await
+    }
+}
+
+/// Creates MAC of keystore, Keccak-256 hash of second half of derived key and ciphertext.
+///
+/// # Arguments
+///
+/// * `derived_key` - key derived from password
+/// * `ciphertext` - encrypted private key
+fn create_mac(derived_key: &[u8; KEYSTORE_DKLEN], ciphertext: &[u8]) -> Vec<u8> {
+    let mut hasher = Keccak256::new();
+    hasher.update(&derived_key[16..]);
+    hasher.update(ciphertext);
+    hasher.finalize().to_vec()
+}
+
+/// Encrypts or decrypts data in place with AES-128-CTR, keyed with first half of derived key.
+///
+/// # Arguments
+///
+/// * `derived_key` - key derived from password
+/// * `iv` - random 16B IV of keystore
+/// * `data` - data to encrypt or decrypt
+fn apply_keystream(derived_key: &[u8; KEYSTORE_DKLEN], iv: &[u8], data: &mut [u8]) -> Result<(), VadeEvanError> {
+    let mut cipher = Ctr128BE::<Aes128>::new_from_slices(&derived_key[..16], iv)
+        .map_err(|_| VadeEvanError::Signature("keystore iv has to have 16 bytes".to_string()))?;
+    cipher.apply_keystream(data);
+    Ok(())
+}
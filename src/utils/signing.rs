@@ -0,0 +1,340 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Creation and verification of `ES256K-R` and `ES256K` JWTs as used in `proof` properties of VCs
+//! and DIDs.
+
+use chrono::{ DateTime, Utc };
+use data_encoding::BASE64URL;
+use secp256k1::{Message, PublicKey, Signature, recover, RecoveryId, verify};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::convert::TryInto;
+use crate::utils::errors::VadeEvanError;
+use crate::utils::signer::Signer;
+use crate::utils::verification_key::VerificationKey;
+
+/// JWS algorithm of secp256k1 signatures with appended recovery id, signer can be recovered
+pub const JWS_ALG_ES256K_R: &str = "ES256K-R";
+/// JWS algorithm of plain secp256k1 signatures, signer has to be verified with its public key
+pub const JWS_ALG_ES256K: &str = "ES256K";
+
+/// Creates proof for a document. The document is signed as JWT with the payload
+/// `{"iat": ..., "<document_key>": document, "iss": issuer, "proof": proof options}`, the proof
+/// options are the created proof without `jws`, so its `challenge`, `created` and `proofPurpose`
+/// are covered by the signature.
+///
+/// # Arguments
+///
+/// * `document` - document to create proof for
+/// * `document_key` - property name of document in JWT payload, e.g. `"vc"` or `"didDocument"`
+/// * `issuer` - DID of issuer, used as `iss` in JWT payload
+/// * `signer` - signer to create proof with, its key id is used as `verificationMethod`
+/// * `alg` - JWS algorithm, [`JWS_ALG_ES256K_R`] or [`JWS_ALG_ES256K`]
+/// * `challenge` - challenge of a verifier to add to proof, if any
+/// * `now` - timestamp of issuing
+pub async fn create_proof(
+    document: &Value,
+    document_key: &str,
+    issuer: &str,
+    signer: &dyn Signer,
+    alg: &str,
+    challenge: Option<&str>,
+    now: &DateTime<Utc>,
+) -> Result<Value, VadeEvanError> {
+    // build proof property as serde object
+    let mut proof = serde_json::json!({
+        "type": "EcdsaPublicKeySecp256k1",
+        "created": format!("{}", now.format("%Y-%m-%dT%H:%M:%S.000Z")),
+        "proofPurpose": "assertionMethod",
+        "verificationMethod": signer.key_id(),
+    });
+    if let Some(challenge) = challenge {
+        proof["challenge"] = Value::from(challenge);
+    }
+
+    // build data object
+    let mut data_json: Value = serde_json::from_str("{}")?;
+    data_json["iat"] = Value::from(now.timestamp());
+    data_json[document_key] = document.clone();
+    data_json["iss"] = Value::from(issuer);
+    data_json["proof"] = proof.clone();
+    proof["jws"] = Value::from(create_jwt(&data_json, signer, alg, None).await?);
+
+    Ok(proof)
+}
+
+/// Creates a JWT for given payload and signs it with `ES256K-R` or `ES256K`. The payload is
+/// canonicalized with [`canonicalize_json`].
+///
+/// # Arguments
+///
+/// * `payload` - payload of JWT
+/// * `signer` - signer to sign with
+/// * `alg` - JWS algorithm, [`JWS_ALG_ES256K_R`] or [`JWS_ALG_ES256K`]
+/// * `kid` - id of signing key, added to header if given
+pub async fn create_jwt(
+    payload: &Value,
+    signer: &dyn Signer,
+    alg: &str,
+    kid: Option<&str>,
+) -> Result<String, VadeEvanError> {
+    // ES256K signatures omit the recovery id
+    let signature_length = match alg {
+        JWS_ALG_ES256K_R => 65,
+        JWS_ALG_ES256K => 64,
+        _ => return Err(VadeEvanError::Unsupported(format!("jws algorithm \"{}\" is not supported", alg))),
+    };
+
+    // create to-be-signed jwt
+    let mut header = serde_json::json!({ "typ": "JWT", "alg": alg });
+    if let Some(kid) = kid {
+        header["kid"] = Value::from(kid);
+    }
+    let header_str = header.to_string();
+    let padded = BASE64URL.encode(header_str.as_bytes());
+    let header_encoded = padded.trim_end_matches('=');
+    debug!("header base64 url encdoded: {:?}", &header_encoded);
+
+    let padded = BASE64URL.encode(canonicalize_json(payload)?.as_bytes());
+    let data_encoded = padded.trim_end_matches('=');
+    debug!("data base64 url encdoded: {:?}", &data_encoded);
+
+    // sign header and data
+    let header_and_data = format!("{}.{}", header_encoded, data_encoded);
+    let sig_and_rec = sign_with_signer(header_and_data.as_bytes(), signer).await?;
+    let padded = BASE64URL.encode(&sig_and_rec[..signature_length]);
+    let sig_base64url = padded.trim_end_matches('=');
+    debug!("signature base64 url encdoded: {:?}", &sig_base64url);
+
+    Ok(format!("{}.{}", &header_and_data, sig_base64url))
+}
+
+/// Signs SHA-256 hash of given message with a signer, returns signature with appended recovery id.
+///
+/// # Arguments
+///
+/// * `message` - message to sign
+/// * `signer` - signer to sign with
+pub async fn sign_with_signer(message: &[u8], signer: &dyn Signer) -> Result<[u8; 65], VadeEvanError> {
+    let hash_arr = hash_message(message)?;
+    debug!("message hash {:?}", hash_arr);
+    signer.sign_digest(&hash_arr).await
+}
+
+/// Recovers Ethereum address of signer and data part of a jwt, the jwt has to be signed with
+/// `ES256K-R`.
+///
+/// # Arguments
+///
+/// * `jwt` - jwt as str&
+pub fn recover_address_and_data(jwt: &str) -> Result<(String, String), VadeEvanError> {
+    let alg = get_jwt_algorithm(jwt)?;
+    if alg != JWS_ALG_ES256K_R {
+        return Err(VadeEvanError::Unsupported(format!(
+            "jwt has alg \"{}\", signer can only be recovered for \"{}\"", alg, JWS_ALG_ES256K_R)));
+    }
+
+    // jwt text parsing
+    let (header, data, signature) = split_jwt(jwt)?;
+    let header_and_data = format!("{}.{}", header, data);
+
+    // recover data for later checks
+    let data_decoded = decode_base64url(data)?;
+    let data_string = String::from_utf8(data_decoded)?;
+
+    // decode signature for validation
+    let signature_decoded = decode_base64url(signature)?;
+    debug!("signature_decoded {:?}", &signature_decoded);
+    debug!("signature_decoded.len {:?}", signature_decoded.len());
+    if signature_decoded.len() != 65 {
+        return Err(VadeEvanError::Signature(format!(
+            "signature has {} bytes, expected 65 bytes (signature and recovery id)", signature_decoded.len())));
+    }
+
+    // create hash of data (including header)
+    let hash_arr = hash_message(header_and_data.as_bytes())?;
+    debug!("header_and_data hash {:?}", hash_arr);
+
+    // prepare arguments for public key recovery
+    let ctx_msg = Message::parse(&hash_arr);
+    let mut signature_array = [0u8; 64];
+    signature_array.copy_from_slice(&signature_decoded[..64]);
+    // slice signature and recovery for recovery
+    debug!("recovery id: {}", signature_decoded[64]);
+    let ctx_sig = Signature::parse(&signature_array);
+    let recovery_id = RecoveryId::parse(signature_decoded[64])?;
+
+    // recover public key, build ethereum address from it
+    let recovered_key = recover(&ctx_msg, &ctx_sig, &recovery_id)?;
+    let address = get_ethereum_address(&recovered_key);
+    debug!("address 0x{}", &address);
+
+    Ok((address, data_string))
+}
+
+/// Decodes header and payload of a JWT without verifying it.
+///
+/// # Arguments
+///
+/// * `jwt` - jwt as str&
+pub fn decode_jwt(jwt: &str) -> Result<(Value, Value), VadeEvanError> {
+    let (header, data, _) = split_jwt(jwt)?;
+    let header: Value = serde_json::from_slice(&decode_base64url(header)?)?;
+    let claims: Value = serde_json::from_slice(&decode_base64url(data)?)?;
+    if !header.is_object() || !claims.is_object() {
+        return Err(VadeEvanError::Parse("jwt header and payload have to be objects".to_string()));
+    }
+    Ok((header, claims))
+}
+
+/// Returns `alg` from header of a JWT.
+///
+/// # Arguments
+///
+/// * `jwt` - jwt as str&
+pub fn get_jwt_algorithm(jwt: &str) -> Result<String, VadeEvanError> {
+    let (header, _, _) = split_jwt(jwt)?;
+    let header: Value = serde_json::from_slice(&decode_base64url(header)?)?;
+    header["alg"].as_str()
+        .map(|alg| alg.to_string())
+        .ok_or_else(|| VadeEvanError::Parse("jwt header has no \"alg\"".to_string()))
+}
+
+/// Verifies `ES256K` signature of a JWT with the signer's key, returns data part of the JWT.
+/// See [`verify_signature`] for supported keys.
+///
+/// # Arguments
+///
+/// * `jwt` - jwt as str&
+/// * `key` - key of signer
+pub fn verify_jwt(jwt: &str, key: &VerificationKey) -> Result<String, VadeEvanError> {
+    let alg = get_jwt_algorithm(jwt)?;
+    if alg != JWS_ALG_ES256K {
+        return Err(VadeEvanError::Signature(format!("jwt has alg \"{}\", expected \"{}\"", alg, JWS_ALG_ES256K)));
+    }
+    let (header, data, signature) = split_jwt(jwt)?;
+    let data_string = String::from_utf8(decode_base64url(data)?)?;
+    verify_signature(format!("{}.{}", header, data).as_bytes(), &decode_base64url(signature)?, key)?;
+
+    Ok(data_string)
+}
+
+/// Verifies 64B `ES256K` signature of SHA-256 hash of given message with the signer's key. Keys
+/// given as Ethereum address are compared with the addresses recoverable from the signature.
+///
+/// # Arguments
+///
+/// * `message` - signed message
+/// * `signature` - signature without recovery id
+/// * `key` - key of signer
+pub fn verify_signature(message: &[u8], signature: &[u8], key: &VerificationKey) -> Result<(), VadeEvanError> {
+    if signature.len() != 64 {
+        return Err(VadeEvanError::Signature(format!(
+            "signature has {} bytes, expected 64 bytes", signature.len())));
+    }
+    let hash_arr = hash_message(message)?;
+    let ctx_msg = Message::parse(&hash_arr);
+    let mut signature_array = [0u8; 64];
+    signature_array.copy_from_slice(signature);
+    let ctx_sig = Signature::parse(&signature_array);
+
+    let is_valid = match key {
+        VerificationKey::Secp256k1(public_key) => verify(&ctx_msg, &ctx_sig, public_key),
+        VerificationKey::EthereumAddress(address) => (0..2).any(|id| {
+            RecoveryId::parse(id)
+                .and_then(|recovery_id| recover(&ctx_msg, &ctx_sig, &recovery_id))
+                .map(|recovered_key| &format!("0x{}", get_ethereum_address(&recovered_key)) == address)
+                .unwrap_or(false)
+        }),
+        VerificationKey::Ed25519(_) =>
+            return Err(VadeEvanError::Signature("Ed25519 keys cannot verify ES256K signatures".to_string())),
+    };
+    match is_valid {
+        true => Ok(()),
+        false => Err(VadeEvanError::Signature("signature does not match key".to_string())),
+    }
+}
+
+/// Serializes JSON value canonically with the JSON Canonicalization Scheme ([RFC 8785]), so equal
+/// documents are serialized equally regardless of property order or number formatting.
+///
+/// RDF dataset normalization (URDNA2015) as used by Linked Data proofs is not supported, as it
+/// requires resolving the documents' JSON-LD contexts.
+///
+/// [RFC 8785]: https://tools.ietf.org/html/rfc8785
+///
+/// # Arguments
+///
+/// * `value` - value to serialize
+pub fn canonicalize_json(value: &Value) -> Result<String, VadeEvanError> {
+    Ok(serde_jcs::to_string(value)?)
+}
+
+/// Builds Ethereum address of a public key, returns it as hex string without `0x` prefix.
+///
+/// # Arguments
+///
+/// * `public_key` - secp256k1 public key
+pub fn get_ethereum_address(public_key: &PublicKey) -> String {
+    let mut hasher = Keccak256::new();
+    hasher.update(&public_key.serialize()[1..65]);
+    let hash = hasher.finalize();
+    debug!("public key hash {:?}", hash);
+    hex::encode(&hash[12..32])
+}
+
+/// Decodes base64url encoded data, with or without padding.
+///
+/// # Arguments
+///
+/// * `data` - base64url encoded data
+pub fn decode_base64url(data: &str) -> Result<Vec<u8>, VadeEvanError> {
+    let padding = match data.len() % 4 {
+        2 => "==",
+        3 => "=",
+        _ => "",
+    };
+    Ok(BASE64URL.decode(format!("{}{}", data, padding).as_bytes())?)
+}
+
+/// Splits JWT into header, data and signature part.
+///
+/// # Arguments
+///
+/// * `jwt` - jwt as str&
+fn split_jwt(jwt: &str) -> Result<(&str, &str, &str), VadeEvanError> {
+    let split: Vec<&str> = jwt.split('.').collect();
+    if split.len() != 3 {
+        return Err(VadeEvanError::Parse(format!("jwt has {} parts, expected 3", split.len())));
+    }
+    Ok((split[0], split[1], split[2]))
+}
+
+/// Creates SHA-256 hash of given message.
+///
+/// # Arguments
+///
+/// * `message` - message to hash
+fn hash_message(message: &[u8]) -> Result<[u8; 32], VadeEvanError> {
+    let mut hasher = Sha256::new();
+    hasher.update(message);
+    hasher.finalize()
+        .as_slice()
+        .try_into()
+        .map_err(|_| VadeEvanError::Signature("hash has invalid length".to_string()))
+}
@@ -0,0 +1,150 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Cache for `credentialStatus` lookups of [`RustVcResolverEvan`].
+//!
+//! [`RustVcResolverEvan`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan
+
+use chrono::{ DateTime, Duration, Utc };
+use crate::utils::cache::{ CacheStats, LruCache };
+
+/// number of statuses kept by [`StatusCache::new`]
+const STATUS_CACHE_CAPACITY: usize = 10000;
+/// last known statuses are kept this many days to be used in [`StatusCacheMode::Degraded`]
+const STATUS_RETENTION_DAYS: i64 = 30;
+
+/// How cached statuses are used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatusCacheMode {
+    /// always fetch status, cached statuses are never used
+    Strict,
+    /// use cached statuses younger than `max_age`, fetch otherwise
+    Cached,
+    /// like `Cached`, but use last known status with a warning if the status cannot be fetched,
+    /// e.g. if the status endpoint is unreachable or answers with an invalid response
+    Degraded,
+}
+
+/// Status of a VC as last fetched from its status endpoint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CachedStatus {
+    /// `true` if VC was active
+    pub active: bool,
+    /// time of fetching status
+    pub fetched: DateTime<Utc>,
+}
+
+/// Cache for VC statuses, clones share their entries. Times are passed in by the caller, so
+/// [`RustVcResolverEvan`] can use its `clock`.
+///
+/// [`RustVcResolverEvan`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan
+#[derive(Clone)]
+pub struct StatusCache {
+    /// how cached statuses are used
+    pub mode: StatusCacheMode,
+    /// maximum age of statuses to use without fetching them again
+    pub max_age: Duration,
+    entries: LruCache<CachedStatus>,
+}
+
+impl StatusCache {
+    /// Creates new instance of `StatusCache`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - how cached statuses are used
+    /// * `max_age` - maximum age of statuses to use without fetching them again
+    pub fn new(mode: StatusCacheMode, max_age: Duration) -> StatusCache {
+        StatusCache::new_with_capacity(mode, max_age, STATUS_CACHE_CAPACITY)
+    }
+
+    /// Creates new instance of `StatusCache` with a custom maximum number of entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - how cached statuses are used
+    /// * `max_age` - maximum age of statuses to use without fetching them again
+    /// * `capacity` - maximum number of statuses to keep
+    pub fn new_with_capacity(mode: StatusCacheMode, max_age: Duration, capacity: usize) -> StatusCache {
+        StatusCache {
+            mode,
+            max_age,
+            entries: LruCache::new(capacity, Duration::days(STATUS_RETENTION_DAYS)),
+        }
+    }
+
+    /// Gets status for given status id, if it may be used without fetching it again. Only these
+    /// lookups count as hits in [`stats`](StatusCache::stats).
+    ///
+    /// # Arguments
+    ///
+    /// * `status_id` - status id / url of VC
+    /// * `now` - current time
+    pub fn get(&self, status_id: &str, now: &DateTime<Utc>) -> Option<CachedStatus> {
+        self.entries.get_matching_at(status_id, now, |status| self.is_fresh(status, now))
+    }
+
+    /// Gets last known status for given status id, regardless of its age. The lookup is not
+    /// counted in [`stats`](StatusCache::stats).
+    ///
+    /// # Arguments
+    ///
+    /// * `status_id` - status id / url of VC
+    /// * `now` - current time
+    pub fn get_last_known(&self, status_id: &str, now: &DateTime<Utc>) -> Option<CachedStatus> {
+        self.entries.peek_at(status_id, now)
+    }
+
+    /// Checks if given status may be used without fetching it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - status returned by [`get_last_known`](StatusCache::get_last_known)
+    /// * `now` - current time
+    pub fn is_fresh(&self, status: &CachedStatus, now: &DateTime<Utc>) -> bool {
+        self.mode != StatusCacheMode::Strict && *now - status.fetched < self.max_age
+    }
+
+    /// Stores freshly fetched status.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_id` - status id / url of VC
+    /// * `active` - `true` if VC is active
+    /// * `now` - time of fetching status
+    pub fn set(&self, status_id: &str, active: bool, now: &DateTime<Utc>) {
+        self.entries.set_at(status_id, CachedStatus { active, fetched: *now }, now);
+    }
+
+    /// Removes status for given status id, returns `true` if a status was removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_id` - status id / url of VC
+    pub fn invalidate(&self, status_id: &str) -> bool {
+        self.entries.invalidate(status_id)
+    }
+
+    /// Removes all statuses.
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// Returns hit, miss and eviction counters.
+    pub fn stats(&self) -> CacheStats {
+        self.entries.stats()
+    }
+}
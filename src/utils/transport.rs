@@ -0,0 +1,214 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! HTTP transports used by resolvers and storages to talk to evan.network nodes.
+//!
+//! [`ReqwestTransport`] is used by default, [`FixtureTransport`] answers requests from
+//! registered responses and can be used to run resolvers without network access.
+
+use async_trait::async_trait;
+use reqwest;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::utils::errors::VadeEvanError;
+
+/// Performs HTTP requests and returns response bodies.
+#[async_trait(?Send)]
+pub trait Transport {
+    /// Sends a GET request and returns the response body, non-2xx responses are returned as
+    /// [`VadeEvanError::Network`].
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL to request
+    async fn get(&self, url: &str) -> Result<String, VadeEvanError>;
+
+    /// Sends a POST request with a JSON body and returns the response body, non-2xx responses are
+    /// returned as [`VadeEvanError::Network`].
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL to post to
+    /// * `body` - JSON body to send
+    async fn post(&self, url: &str, body: &str) -> Result<String, VadeEvanError>;
+}
+
+/// Sends requests with [`reqwest`](https://docs.rs/reqwest).
+#[derive(Clone)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    /// headers added to each request, e.g. for authentication
+    pub headers: HashMap<String, String>,
+}
+
+impl ReqwestTransport {
+    /// Creates new instance of `ReqwestTransport` with a default client.
+    pub fn new() -> ReqwestTransport {
+        ReqwestTransport::new_with_client(reqwest::Client::new())
+    }
+
+    /// Creates new instance of `ReqwestTransport` with a preconfigured client, e.g. to use proxies or
+    /// custom root certificates.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - client to send requests with
+    pub fn new_with_client(client: reqwest::Client) -> ReqwestTransport {
+        ReqwestTransport {
+            client,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Adds a header that is sent with each request.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - header name, e.g. `"Authorization"`
+    /// * `value` - header value
+    pub fn with_header(mut self, name: &str, value: &str) -> ReqwestTransport {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Adds configured headers to request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - request to add headers to
+    fn add_headers(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        request
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> ReqwestTransport {
+        ReqwestTransport::new()
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport for ReqwestTransport {
+    async fn get(&self, url: &str) -> Result<String, VadeEvanError> {
+        let body = self.add_headers(self.client.get(url))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(body)
+    }
+
+    async fn post(&self, url: &str, body: &str) -> Result<String, VadeEvanError> {
+        let body = self.add_headers(self.client.post(url))
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(body)
+    }
+}
+
+/// Request sent to a [`FixtureTransport`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransportRequest {
+    /// HTTP method, `"GET"` or `"POST"`
+    pub method: String,
+    /// requested URL
+    pub url: String,
+    /// body of POST requests
+    pub body: Option<String>,
+}
+
+/// Answers requests with registered responses, requests without a registered response fail with
+/// a [`VadeEvanError::Network`] error. Clones share responses and recorded requests, so a clone
+/// can be handed to a resolver while the original is used to inspect requests.
+#[derive(Clone)]
+pub struct FixtureTransport {
+    responses: Rc<RefCell<HashMap<String, String>>>,
+    requests: Rc<RefCell<Vec<TransportRequest>>>,
+}
+
+impl FixtureTransport {
+    /// Creates new instance of `FixtureTransport` without any responses.
+    pub fn new() -> FixtureTransport {
+        FixtureTransport {
+            responses: Rc::new(RefCell::new(HashMap::new())),
+            requests: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Registers a response and returns transport, see [`add_response`].
+    ///
+    /// [`add_response`]: FixtureTransport#method.add_response
+    pub fn with_response(self, method: &str, url: &str, body: &str) -> FixtureTransport {
+        self.add_response(method, url, body);
+        self
+    }
+
+    /// Registers a response, existing responses for the same request are replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method, `"GET"` or `"POST"`
+    /// * `url` - URL to respond to
+    /// * `body` - response body
+    pub fn add_response(&self, method: &str, url: &str, body: &str) {
+        self.responses.borrow_mut().insert(format!("{} {}", method, url), body.to_string());
+    }
+
+    /// Returns all requests sent to this transport (and its clones) so far.
+    pub fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.borrow().clone()
+    }
+
+    /// Records request and returns registered response.
+    fn respond(&self, method: &str, url: &str, body: Option<&str>) -> Result<String, VadeEvanError> {
+        self.requests.borrow_mut().push(TransportRequest {
+            method: method.to_string(),
+            url: url.to_string(),
+            body: body.map(|b| b.to_string()),
+        });
+        match self.responses.borrow().get(&format!("{} {}", method, url)) {
+            Some(response) => Ok(response.to_string()),
+            None => Err(VadeEvanError::Network(format!("no fixture for \"{} {}\"", method, url))),
+        }
+    }
+}
+
+impl Default for FixtureTransport {
+    fn default() -> FixtureTransport {
+        FixtureTransport::new()
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport for FixtureTransport {
+    async fn get(&self, url: &str) -> Result<String, VadeEvanError> {
+        self.respond("GET", url, None)
+    }
+
+    async fn post(&self, url: &str, body: &str) -> Result<String, VadeEvanError> {
+        self.respond("POST", url, Some(body))
+    }
+}
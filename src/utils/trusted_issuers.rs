@@ -0,0 +1,126 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Registry of issuers trusted to issue VCs of given credential types.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use crate::utils::errors::VadeEvanError;
+
+/// Lists issuer DIDs trusted per credential `type`. A VC is accepted if its issuer is trusted for at
+/// least one of its types, so issuers listed for `VerifiableCredential` are trusted for all VCs.
+///
+/// Registries can be loaded from JSON objects mapping types to lists of DIDs, e.g.
+///
+/// ```json
+/// {
+///   "SupplierCredential": [ "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f" ]
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrustedIssuerRegistry {
+    issuers: HashMap<String, Vec<String>>,
+}
+
+impl TrustedIssuerRegistry {
+    /// Creates new instance of `TrustedIssuerRegistry` without trusted issuers.
+    pub fn new() -> TrustedIssuerRegistry {
+        TrustedIssuerRegistry { issuers: HashMap::new() }
+    }
+
+    /// Parses registry from a JSON object mapping credential types to lists of issuer DIDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - registry as JSON string
+    pub fn from_json(json: &str) -> Result<TrustedIssuerRegistry, VadeEvanError> {
+        let parsed: Value = serde_json::from_str(json)?;
+        let types = parsed.as_object()
+            .ok_or_else(|| VadeEvanError::Schema("trusted issuer registry is not an object".to_string()))?;
+        let mut registry = TrustedIssuerRegistry::new();
+        for (credential_type, issuers) in types {
+            let issuers = issuers.as_array()
+                .ok_or_else(|| VadeEvanError::Schema(format!("issuers for \"{}\" are not an array", credential_type)))?;
+            for issuer in issuers {
+                let issuer = issuer.as_str()
+                    .ok_or_else(|| VadeEvanError::Schema(format!("issuers for \"{}\" have to be DIDs", credential_type)))?;
+                registry = registry.with_issuer(credential_type, issuer);
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Loads registry from a JSON file, see [`from_json`](TrustedIssuerRegistry::from_json).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - path to JSON file
+    pub fn from_file(path: &str) -> Result<TrustedIssuerRegistry, VadeEvanError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| VadeEvanError::Config(format!("could not read trusted issuer registry \"{}\", {}", path, e)))?;
+        TrustedIssuerRegistry::from_json(&json)
+    }
+
+    /// Trusts issuer for given credential type.
+    ///
+    /// # Arguments
+    ///
+    /// * `credential_type` - credential type, e.g. `"VerifiableCredential"`
+    /// * `issuer` - DID of issuer
+    pub fn with_issuer(mut self, credential_type: &str, issuer: &str) -> TrustedIssuerRegistry {
+        let issuers = self.issuers.entry(credential_type.to_string()).or_default();
+        if !issuers.iter().any(|trusted| trusted == issuer) {
+            issuers.push(issuer.to_string());
+        }
+        self
+    }
+
+    /// Checks if issuer is trusted for at least one of given credential types.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - DID of issuer
+    /// * `credential_types` - `type`s of VC
+    pub fn is_trusted(&self, issuer: &str, credential_types: &[String]) -> bool {
+        credential_types.iter()
+            .filter_map(|credential_type| self.issuers.get(credential_type))
+            .any(|issuers| issuers.iter().any(|trusted| trusted == issuer))
+    }
+
+    /// Like [`is_trusted`](TrustedIssuerRegistry::is_trusted), but returns an error naming the
+    /// untrusted issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - DID of issuer
+    /// * `credential_types` - `type`s of VC
+    pub fn check(&self, issuer: &str, credential_types: &[String]) -> Result<(), VadeEvanError> {
+        match self.is_trusted(issuer, credential_types) {
+            true => Ok(()),
+            false => Err(VadeEvanError::Policy(format!(
+                "issuer \"{}\" is not trusted to issue VCs of type \"{}\"",
+                issuer,
+                credential_types.join("\", \""),
+            ))),
+        }
+    }
+}
+
+impl Default for TrustedIssuerRegistry {
+    fn default() -> TrustedIssuerRegistry {
+        TrustedIssuerRegistry::new()
+    }
+}
@@ -0,0 +1,213 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Storage backends for VCs, used by [`RustVcResolverEvan`] to persist VCs and to assign ids to new VCs.
+//!
+//! [`RustVcResolverEvan`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::cell::{ Cell, RefCell };
+use std::collections::HashMap;
+use crate::utils::config::ResolverConfig;
+use crate::utils::errors::VadeEvanError;
+use crate::utils::transport::{ ReqwestTransport, Transport };
+
+/// Backend for storing VCs.
+#[async_trait(?Send)]
+pub trait VcStorage {
+    /// Creates a new id for a VC issued by given DID.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - DID of VC issuer, e.g. `"did:evan:testcore:0x..."`
+    async fn create_id(&self, issuer: &str) -> Result<String, VadeEvanError>;
+
+    /// Gets VC document for given id.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of VC to get
+    async fn get(&self, vc_id: &str) -> Result<String, VadeEvanError>;
+
+    /// Stores VC document with given id.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of VC to store
+    /// * `document` - VC document as JSON string
+    async fn set(&mut self, vc_id: &str, document: &str) -> Result<(), VadeEvanError>;
+}
+
+/// Stores VCs in the VC registry of [evan.network](https://evan.network/) nodes.
+pub struct EvanVcStorage {
+    pub config: ResolverConfig,
+    /// transport used to send requests to nodes
+    pub transport: Box<dyn Transport>,
+}
+
+impl EvanVcStorage {
+    /// Creates new instance of `EvanVcStorage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - config with endpoints to use per network
+    pub fn new(config: ResolverConfig) -> EvanVcStorage {
+        EvanVcStorage::new_with_transport(config, Box::new(ReqwestTransport::new()))
+    }
+
+    /// Creates new instance of `EvanVcStorage` with custom transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - config with endpoints to use per network
+    /// * `transport` - transport to send requests with
+    pub fn new_with_transport(config: ResolverConfig, transport: Box<dyn Transport>) -> EvanVcStorage {
+        EvanVcStorage { config, transport }
+    }
+
+    /// Posts given payload to node and returns parsed response.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL to post to
+    /// * `payload` - payload to send as JSON
+    async fn post(&self, url: &str, payload: &Value) -> Result<Value, VadeEvanError> {
+        let body = self.transport.post(url, &payload.to_string()).await?;
+        let parsed: Value = serde_json::from_str(&body)?;
+        if parsed["status"] == "error" {
+            Err(VadeEvanError::Network(format!("vc registry error, {:?}", parsed["error"].as_str().unwrap_or_default())))
+        } else {
+            Ok(parsed)
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl VcStorage for EvanVcStorage {
+    /// Not supported, evan.network nodes do not offer an endpoint to create VC ids, so ids have to
+    /// be provided when storing VCs in their VC registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - DID of VC issuer, e.g. `"did:evan:testcore:0x..."`
+    async fn create_id(&self, issuer: &str) -> Result<String, VadeEvanError> {
+        Err(VadeEvanError::Unsupported(format!(
+            "cannot create vc id for issuer \"{}\", evan.network nodes do not create vc ids", issuer)))
+    }
+
+    /// Gets VC document from VC registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of VC to get
+    async fn get(&self, vc_id: &str) -> Result<String, VadeEvanError> {
+        let url = self.config.get_url("vc", vc_id)?;
+        let body = self.transport.get(&url).await?;
+        let parsed: Value = serde_json::from_str(&body)?;
+        if parsed["status"] == "error" {
+            Err(VadeEvanError::Network(format!("could not get vc document, {:?}", parsed["error"].as_str().unwrap_or_default())))
+        } else {
+            Ok(serde_json::to_string(&parsed["vc"])?)
+        }
+    }
+
+    /// Stores VC document in VC registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of VC to store
+    /// * `document` - VC document as JSON string
+    async fn set(&mut self, vc_id: &str, document: &str) -> Result<(), VadeEvanError> {
+        let url = self.config.get_url("vc", vc_id)?;
+        let mut payload: Value = serde_json::from_str("{}")?;
+        payload["vc"] = serde_json::from_str(document)?;
+        self.post(&url, &payload).await?;
+        Ok(())
+    }
+}
+
+/// Stores VCs in memory, e.g. for VCs that are only exchanged offline or for testing.
+pub struct InMemoryVcStorage {
+    documents: RefCell<HashMap<String, String>>,
+    nonce: Cell<u64>,
+}
+
+impl InMemoryVcStorage {
+    /// Creates new instance of `InMemoryVcStorage`.
+    pub fn new() -> InMemoryVcStorage {
+        InMemoryVcStorage {
+            documents: RefCell::new(HashMap::new()),
+            nonce: Cell::new(0),
+        }
+    }
+}
+
+impl Default for InMemoryVcStorage {
+    fn default() -> InMemoryVcStorage {
+        InMemoryVcStorage::new()
+    }
+}
+
+#[async_trait(?Send)]
+impl VcStorage for InMemoryVcStorage {
+    /// Creates a new id in the issuer's network, e.g. `"vc:evan:testcore:0x..."`, ids are derived
+    /// from issuer, current time and an internal counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - DID of VC issuer, e.g. `"did:evan:testcore:0x..."`
+    async fn create_id(&self, issuer: &str) -> Result<String, VadeEvanError> {
+        let network = ResolverConfig::get_network(issuer)?;
+        let nonce = self.nonce.get() + 1;
+        self.nonce.set(nonce);
+        let mut hasher = Keccak256::new();
+        let now = Utc::now();
+        hasher.update(format!("{}:{}.{}:{}", issuer, now.timestamp(), now.timestamp_subsec_nanos(), nonce));
+        let hash = hasher.finalize();
+        let vc_id = match network {
+            Some(network) => format!("vc:evan:{}:0x{}", network, hex::encode(hash)),
+            None => format!("vc:evan:0x{}", hex::encode(hash)),
+        };
+
+        Ok(vc_id)
+    }
+
+    /// Gets VC document from memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of VC to get
+    async fn get(&self, vc_id: &str) -> Result<String, VadeEvanError> {
+        match self.documents.borrow().get(vc_id) {
+            Some(document) => Ok(document.to_string()),
+            None => Err(VadeEvanError::Network(format!("could not get vc document, \"{}\" not found", vc_id))),
+        }
+    }
+
+    /// Stores VC document in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of VC to store
+    /// * `document` - VC document as JSON string
+    async fn set(&mut self, vc_id: &str, document: &str) -> Result<(), VadeEvanError> {
+        self.documents.borrow_mut().insert(vc_id.to_string(), document.to_string());
+        Ok(())
+    }
+}
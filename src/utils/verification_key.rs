@@ -0,0 +1,132 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Key material of verification methods in DID documents.
+
+use secp256k1::PublicKey;
+use serde_json::Value;
+use crate::utils::errors::VadeEvanError;
+use crate::utils::signing::{ decode_base64url, get_ethereum_address };
+
+/// Key of a verification method, as used to check proofs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerificationKey {
+    /// Ethereum address of a secp256k1 key, from `ethereumAddress`
+    EthereumAddress(String),
+    /// secp256k1 public key, from `publicKeyHex`, `publicKeyJwk` or `publicKeyBase58`
+    Secp256k1(PublicKey),
+    /// Ed25519 public key, from `publicKeyHex`, `publicKeyJwk` or `publicKeyBase58`
+    Ed25519(ed25519_dalek::PublicKey),
+}
+
+impl VerificationKey {
+    /// Parses key material of a verification method. Keys are taken from the first of these
+    /// properties found:
+    /// - `ethereumAddress`: `0x` prefixed address
+    /// - `publicKeyHex`: compressed or uncompressed secp256k1 key, optionally `0x` prefixed
+    /// - `publicKeyJwk`: JWK with `kty` `"EC"`, `crv` `"secp256k1"` and coordinates `x` and `y`
+    /// - `publicKeyBase58`: compressed or uncompressed secp256k1 key
+    ///
+    /// Keys of methods with a `type` starting with `Ed25519` and JWKs with `kty` `"OKP"` and
+    /// `crv` `"Ed25519"` are parsed as 32B Ed25519 keys instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - verification method, e.g. entry of `publicKey` in a DID document
+    pub fn parse(method: &Value) -> Result<VerificationKey, VadeEvanError> {
+        let id = method["id"].as_str().unwrap_or_default();
+        let invalid = |property: &str, reason: &str| VadeEvanError::Schema(format!(
+            "key \"{}\" has an invalid \"{}\", {}", id, property, reason));
+        if let Some(address) = method["ethereumAddress"].as_str() {
+            return Ok(VerificationKey::EthereumAddress(address.to_lowercase()));
+        }
+        let is_ed25519 = matches!(method["type"].as_str(), Some(key_type) if key_type.starts_with("Ed25519"));
+        let parse_bytes = |property: &str, bytes: &[u8], is_ed25519: bool| match is_ed25519 {
+            true => parse_ed25519(bytes),
+            false => parse_secp256k1(bytes),
+        }.map_err(|e| invalid(property, &e));
+        if let Some(key) = method["publicKeyHex"].as_str() {
+            let bytes = hex::decode(key.trim_start_matches("0x"))
+                .map_err(|e| invalid("publicKeyHex", &e.to_string()))?;
+            return parse_bytes("publicKeyHex", &bytes, is_ed25519);
+        }
+        if method["publicKeyJwk"].is_object() {
+            let jwk = &method["publicKeyJwk"];
+            let (coordinates, prefix, is_ed25519): (&[&str], &[u8], bool) = match (jwk["kty"].as_str(), jwk["crv"].as_str()) {
+                (Some("EC"), Some("secp256k1")) => (&["x", "y"], &[4u8], false),
+                (Some("OKP"), Some("Ed25519")) => (&["x"], &[], true),
+                _ => return Err(VadeEvanError::Unsupported(format!(
+                    "key \"{}\" has an unsupported \"publicKeyJwk\", only EC keys on secp256k1 and OKP keys on Ed25519 are supported", id))),
+            };
+            let mut bytes = prefix.to_vec();
+            for coordinate in coordinates {
+                let encoded = jwk[*coordinate].as_str()
+                    .ok_or_else(|| invalid("publicKeyJwk", &format!("\"{}\" is missing", coordinate)))?;
+                let decoded = decode_base64url(encoded)
+                    .map_err(|e| invalid("publicKeyJwk", &e.to_string()))?;
+                if decoded.len() != 32 {
+                    return Err(invalid("publicKeyJwk", &format!("\"{}\" has {} bytes, expected 32", coordinate, decoded.len())));
+                }
+                bytes.extend(decoded);
+            }
+            return parse_bytes("publicKeyJwk", &bytes, is_ed25519);
+        }
+        if let Some(key) = method["publicKeyBase58"].as_str() {
+            let bytes = bs58::decode(key).into_vec()
+                .map_err(|e| invalid("publicKeyBase58", &e.to_string()))?;
+            return parse_bytes("publicKeyBase58", &bytes, is_ed25519);
+        }
+        Err(VadeEvanError::Schema(format!(
+            "key \"{}\" has no \"ethereumAddress\", \"publicKeyHex\", \"publicKeyJwk\" or \"publicKeyBase58\"", id)))
+    }
+
+    /// Returns `0x` prefixed Ethereum address of secp256k1 keys in lower case.
+    pub fn ethereum_address(&self) -> Result<String, VadeEvanError> {
+        match self {
+            VerificationKey::EthereumAddress(address) => Ok(address.to_string()),
+            VerificationKey::Secp256k1(public_key) => Ok(format!("0x{}", get_ethereum_address(public_key))),
+            VerificationKey::Ed25519(_) => Err(VadeEvanError::Signature("Ed25519 keys have no Ethereum address".to_string())),
+        }
+    }
+}
+
+/// Parses compressed (33B) or uncompressed (65B) secp256k1 public key.
+///
+/// # Arguments
+///
+/// * `bytes` - serialized public key
+fn parse_secp256k1(bytes: &[u8]) -> Result<VerificationKey, String> {
+    match bytes.len() {
+        33 | 65 => PublicKey::parse_slice(bytes, None)
+            .map(VerificationKey::Secp256k1)
+            .map_err(|e| format!("not a secp256k1 public key, {}", e)),
+        len => Err(format!("key has {} bytes, expected 33 or 65", len)),
+    }
+}
+
+/// Parses 32B Ed25519 public key.
+///
+/// # Arguments
+///
+/// * `bytes` - serialized public key
+fn parse_ed25519(bytes: &[u8]) -> Result<VerificationKey, String> {
+    match bytes.len() {
+        32 => ed25519_dalek::PublicKey::from_bytes(bytes)
+            .map(VerificationKey::Ed25519)
+            .map_err(|e| format!("not an Ed25519 public key, {}", e)),
+        len => Err(format!("key has {} bytes, expected 32", len)),
+    }
+}
@@ -0,0 +1,140 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Policy for checking VCs with [`RustVcResolverEvan`].
+//!
+//! [`RustVcResolverEvan`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan
+
+use crate::utils::detached_jws::{ PROOF_TYPE_ED25519_SIGNATURE_2018, PROOF_TYPE_JSON_WEB_SIGNATURE_2020 };
+use crate::utils::errors::VadeEvanError;
+use crate::utils::trusted_issuers::TrustedIssuerRegistry;
+use crate::utils::verification_report::{ CheckStatus, CheckType, VerificationReport };
+
+/// default proof type of VCs created by [`create_vc_with_signer`](crate::plugin::rust_vcresolver_evan::RustVcResolverEvan::create_vc_with_signer)
+pub const PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1: &str = "EcdsaPublicKeySecp256k1";
+/// `credentialStatus` type of VCs in the evan.network VC registry
+pub const STATUS_TYPE_EVAN_CREDENTIAL: &str = "evan:evanCredential";
+
+/// Configures which VCs are accepted by [`check_vc`] and related functions.
+///
+/// [`check_vc`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan::check_vc
+#[derive(Clone, Debug)]
+pub struct VerificationPolicy {
+    /// accept VCs without `proof`, disabled by default
+    pub allow_unsigned: bool,
+    /// checks that have to be performed, VCs for which one of them has been skipped are rejected,
+    /// e.g. [`CheckType::CredentialStatus`] rejects VCs without `credentialStatus`
+    pub required_checks: Vec<CheckType>,
+    /// accepted values for `proof.type`
+    pub allowed_proof_types: Vec<String>,
+    /// issuers accepted per credential type, all issuers are accepted if omitted
+    pub trusted_issuers: Option<TrustedIssuerRegistry>,
+    /// accepted values for `credentialStatus.type`, all types are accepted if omitted; statuses of
+    /// types other than `evan:evanCredential` cannot be queried and their check is skipped
+    pub allowed_status_types: Option<Vec<String>>,
+    /// do not send any lookups, statuses from `status_cache` are used if available and DID
+    /// documents are only taken from `did_cache`, checks of VCs with DIDs not cached there fail
+    pub offline: bool,
+}
+
+impl VerificationPolicy {
+    /// Creates new instance of `VerificationPolicy` with default settings:
+    /// - VCs without `proof` are rejected
+    /// - no checks are required
+    /// - `EcdsaPublicKeySecp256k1`, `Ed25519Signature2018` and `JsonWebSignature2020` proofs are accepted
+    /// - all issuers and status types are accepted
+    /// - statuses and DIDs are queried online
+    pub fn new() -> VerificationPolicy {
+        VerificationPolicy {
+            allow_unsigned: false,
+            required_checks: Vec::new(),
+            allowed_proof_types: vec![
+                PROOF_TYPE_ECDSA_PUBLIC_KEY_SECP256K1.to_string(),
+                PROOF_TYPE_ED25519_SIGNATURE_2018.to_string(),
+                PROOF_TYPE_JSON_WEB_SIGNATURE_2020.to_string(),
+            ],
+            trusted_issuers: None,
+            allowed_status_types: None,
+            offline: false,
+        }
+    }
+
+    /// Checks if proofs of given type are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof_type` - value of `proof.type`
+    pub fn check_proof_type(&self, proof_type: &str) -> Result<(), VadeEvanError> {
+        match self.allowed_proof_types.iter().any(|allowed| allowed == proof_type) {
+            true => Ok(()),
+            false => Err(VadeEvanError::Policy(format!("proof type \"{}\" is not allowed", proof_type))),
+        }
+    }
+
+    /// Checks if VCs of given issuer and types are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - DID of issuer
+    /// * `credential_types` - `type`s of VC
+    pub fn check_issuer(&self, issuer: &str, credential_types: &[String]) -> Result<(), VadeEvanError> {
+        match &self.trusted_issuers {
+            Some(trusted_issuers) => trusted_issuers.check(issuer, credential_types),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks if statuses of given type are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `status_type` - value of `credentialStatus.type`
+    pub fn check_status_type(&self, status_type: &str) -> Result<(), VadeEvanError> {
+        match &self.allowed_status_types {
+            Some(allowed) if !allowed.iter().any(|allowed| allowed == status_type) =>
+                Err(VadeEvanError::Policy(format!("credentialStatus type \"{}\" is not allowed", status_type))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails first required check that has been skipped or not performed. Reports that already
+    /// contain a failed check are not changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - report to update
+    pub fn enforce_required_checks(&self, report: &mut VerificationReport) {
+        if !report.is_valid() {
+            return;
+        }
+        for check in &self.required_checks {
+            let error = match report.get(*check) {
+                Some(CheckStatus::Passed) | Some(CheckStatus::Warning(_)) => continue,
+                Some(CheckStatus::Skipped(reason)) =>
+                    VadeEvanError::Policy(format!("required check {:?} has been skipped, {}", check, reason)),
+                _ => VadeEvanError::Policy(format!("required check {:?} has not been performed", check)),
+            };
+            report.set(*check, CheckStatus::Failed(error));
+            return;
+        }
+    }
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> VerificationPolicy {
+        VerificationPolicy::new()
+    }
+}
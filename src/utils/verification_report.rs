@@ -0,0 +1,168 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Detailed results of VC checks, returned by [`verify_vc_detailed`].
+//!
+//! [`verify_vc_detailed`]: crate::plugin::rust_vcresolver_evan::RustVcResolverEvan::verify_vc_detailed
+
+use crate::utils::errors::VadeEvanError;
+
+/// Checks performed on VCs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CheckType {
+    /// document is a JSON object and has all properties needed for the other checks
+    Schema,
+    /// signer address can be recovered from the `jws` of the proof
+    SignatureRecovery,
+    /// document signed in `jws` equals given document
+    DocumentEquality,
+    /// key from `verificationMethod` is found in the issuer's DID and matches the signer address
+    KeyLookup,
+    /// `credentialStatus` reports VC as active
+    CredentialStatus,
+    /// VC is within its validity period
+    Expiry,
+    /// issuer of VC is trusted
+    TrustedIssuer,
+    /// signature in `jws` is valid for the key from `verificationMethod`, for proofs that do not
+    /// allow recovering the signer
+    SignatureVerification,
+    /// `challenge` of proof has not been used before
+    Challenge,
+}
+
+/// Outcome of a single check.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckStatus {
+    Passed,
+    /// check passed with degraded guarantees, e.g. a cached status has been used
+    Warning(String),
+    Failed(VadeEvanError),
+    /// check has not been performed, e.g. because VC has no `credentialStatus`
+    Skipped(String),
+}
+
+/// Result of a single check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckResult {
+    pub check: CheckType,
+    pub status: CheckStatus,
+}
+
+/// Describes how the key used for a proof has been authorized by the VC issuer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyAuthorization {
+    /// `verificationMethod` of proof
+    pub verification_method: String,
+    /// DIDs followed via `controller` from issuer to the DID authorizing the key, starts with issuer
+    pub controller_path: Vec<String>,
+    /// verification relationship listing the key, e.g. `"assertionMethod"`, `None` if the
+    /// authorizing DID has no verification relationships and the key is only listed in `publicKey`
+    pub relationship: Option<String>,
+}
+
+/// Checks performed on a VC in order of execution. Checking stops at the first failed check,
+/// so checks after it are not listed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerificationReport {
+    /// id of checked VC
+    pub vc_id: String,
+    pub checks: Vec<CheckResult>,
+    /// how the key of the proof has been authorized, set if [`CheckType::KeyLookup`] passed
+    pub key_authorization: Option<KeyAuthorization>,
+}
+
+impl VerificationReport {
+    /// Creates new instance of `VerificationReport` without checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `vc_id` - id of checked VC
+    pub fn new(vc_id: &str) -> VerificationReport {
+        VerificationReport {
+            vc_id: vc_id.to_string(),
+            checks: Vec::new(),
+            key_authorization: None,
+        }
+    }
+
+    /// Adds result of a check.
+    ///
+    /// # Arguments
+    ///
+    /// * `check` - performed check
+    /// * `status` - outcome of check
+    pub fn add(&mut self, check: CheckType, status: CheckStatus) {
+        self.checks.push(CheckResult { check, status });
+    }
+
+    /// Sets status of given check, replaces existing status or adds check if not performed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `check` - check to set status for
+    /// * `status` - outcome of check
+    pub fn set(&mut self, check: CheckType, status: CheckStatus) {
+        match self.checks.iter_mut().find(|result| result.check == check) {
+            Some(result) => result.status = status,
+            None => self.add(check, status),
+        }
+    }
+
+    /// Gets status of given check, `None` if check has not been performed.
+    ///
+    /// # Arguments
+    ///
+    /// * `check` - check to get status for
+    pub fn get(&self, check: CheckType) -> Option<&CheckStatus> {
+        self.checks.iter()
+            .find(|result| result.check == check)
+            .map(|result| &result.status)
+    }
+
+    /// Returns `true` if no check failed.
+    pub fn is_valid(&self) -> bool {
+        self.error().is_none()
+    }
+
+    /// Returns error of the failed check, if any.
+    pub fn error(&self) -> Option<&VadeEvanError> {
+        self.checks.iter()
+            .filter_map(|result| match &result.status {
+                CheckStatus::Failed(e) => Some(e),
+                _ => None,
+            })
+            .next()
+    }
+
+    /// Returns messages of all checks that passed with warnings.
+    pub fn warnings(&self) -> Vec<String> {
+        self.checks.iter()
+            .filter_map(|result| match &result.status {
+                CheckStatus::Warning(warning) => Some(warning.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Converts report to the error of the failed check or to its warnings if no check failed.
+    pub fn into_result(self) -> Result<Vec<String>, VadeEvanError> {
+        match self.error() {
+            Some(e) => Err(e.clone()),
+            None => Ok(self.warnings()),
+        }
+    }
+}
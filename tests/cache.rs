@@ -0,0 +1,82 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+use chrono::Duration;
+use vade_evan::utils::cache::{ CacheStats, LruCache };
+
+#[test]
+fn can_get_set_and_invalidate_entries() {
+    let cache: LruCache<String> = LruCache::new(10, Duration::minutes(5));
+    assert_eq!(cache.get("a"), None);
+    cache.set("a", "1".to_string());
+    cache.set("b", "2".to_string());
+    assert_eq!(cache.get("a"), Some("1".to_string()));
+    assert_eq!(cache.len(), 2);
+
+    assert!(cache.invalidate("a"));
+    assert!(!cache.invalidate("a"));
+    assert_eq!(cache.get("a"), None);
+    assert_eq!(cache.get("b"), Some("2".to_string()));
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2, evictions: 0 });
+}
+
+#[test]
+fn drops_expired_entries() {
+    let cache: LruCache<String> = LruCache::new(10, Duration::minutes(5));
+    cache.set_with_ttl("a", "1".to_string(), Duration::zero());
+    cache.set_with_ttl("b", "2".to_string(), Duration::milliseconds(50));
+    cache.set("c", "3".to_string());
+    assert_eq!(cache.get("a"), None);
+    assert_eq!(cache.get("b"), Some("2".to_string()));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(cache.get("b"), None);
+    assert_eq!(cache.get("c"), Some("3".to_string()));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn evicts_least_recently_used_entries() {
+    let cache: LruCache<u32> = LruCache::new(2, Duration::minutes(5));
+    cache.set("a", 1);
+    cache.set("b", 2);
+    cache.get("a");
+    cache.set("c", 3);
+    assert_eq!(cache.get("b"), None);
+    assert_eq!(cache.get("a"), Some(1));
+    assert_eq!(cache.get("c"), Some(3));
+    assert_eq!(cache.stats().evictions, 1);
+
+    // updating existing entries does not evict others
+    cache.set("a", 4);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("a"), Some(4));
+}
+
+#[test]
+fn clones_share_entries() {
+    let cache: LruCache<u32> = LruCache::new(2, Duration::minutes(5));
+    let clone = cache.clone();
+    clone.set("a", 1);
+    assert_eq!(cache.get("a"), Some(1));
+    cache.invalidate("a");
+    assert_eq!(clone.get("a"), None);
+    assert_eq!(clone.stats(), cache.stats());
+}
@@ -0,0 +1,115 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+//! Helpers shared between integration tests.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{ BufRead, BufReader, Read, Write };
+use std::net::TcpListener;
+use std::sync::{ Arc, Mutex };
+use std::thread;
+
+/// request received by [`MockServer`]
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    /// headers with lowercase names
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+/// Minimal HTTP server on localhost, answers requests with canned JSON responses and records them.
+pub struct MockServer {
+    /// base URL of server, e.g. `"http://127.0.0.1:12345"`
+    pub url: String,
+    pub requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Starts server in a background thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - response bodies per `"METHOD /path"`, unknown routes are answered with an error
+    pub fn start(routes: HashMap<String, String>) -> MockServer {
+        MockServer::start_with_status(routes.into_iter().map(|(route, body)| (route, (200, body))).collect())
+    }
+
+    /// Starts server in a background thread, answering with given HTTP status codes.
+    ///
+    /// # Arguments
+    ///
+    /// * `routes` - status codes and response bodies per `"METHOD /path"`, unknown routes are
+    ///   answered with an error
+    pub fn start_with_status(routes: HashMap<String, (u16, String)>) -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let thread_requests = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = HashMap::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    let mut header = line.splitn(2, ':');
+                    let name = header.next().unwrap_or_default().trim().to_lowercase();
+                    let value = header.next().unwrap_or_default().trim().to_string();
+                    headers.insert(name, value);
+                }
+                let content_length = headers.get("content-length").map_or(0, |length| length.parse().unwrap());
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let split: Vec<&str> = request_line.split(' ').collect();
+                let request = MockRequest {
+                    method: split[0].to_string(),
+                    path: split[1].to_string(),
+                    headers,
+                    body: String::from_utf8(body).unwrap(),
+                };
+                let (status, response) = match routes.get(&format!("{} {}", request.method, request.path)) {
+                    Some((status, response)) => (*status, response.to_string()),
+                    None => (200, r#"{"status":"error","error":"not found"}"#.to_string()),
+                };
+                thread_requests.lock().unwrap().push(request);
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    response.len(),
+                    response,
+                ).unwrap();
+            }
+        });
+
+        MockServer { url, requests }
+    }
+}
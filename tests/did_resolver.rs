@@ -16,9 +16,20 @@
 
 extern crate vade_evan;
 
+mod common;
+
+use common::MockServer;
+use chrono::Duration;
+use std::collections::HashMap;
 use vade::Vade;
 use vade::plugin::rust_storage_cache::RustStorageCache;
 use vade_evan::plugin::rust_didresolver_evan::RustDidResolverEvan;
+use vade_evan::utils::cache::LruCache;
+use vade_evan::utils::config::ResolverConfig;
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::signer::InMemorySigner;
+use vade_evan::utils::signing::recover_address_and_data;
+use vade_evan::utils::transport::FixtureTransport;
 use serde_json::Value;
 
 const EXAMPLE_DID: &str = "did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906";
@@ -49,9 +60,20 @@ const EXAMPLE_DID_DOCUMENT_STR: &str = r###"
   }
 "###;
 
+/// Creates resolver that answers requests for `EXAMPLE_DID` from fixtures.
+fn get_did_resolver() -> RustDidResolverEvan {
+    let transport = FixtureTransport::new()
+        .with_response(
+            "GET",
+            &format!("https://testcore.evan.network/did/{}", EXAMPLE_DID),
+            &format!(r#"{{"status":"success","did":{}}}"#, EXAMPLE_DID_DOCUMENT_STR),
+        );
+    RustDidResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(transport))
+}
+
 #[tokio::test]
 async fn can_fetch_a_did_document() {
-    let rde = RustDidResolverEvan::new();
+    let rde = get_did_resolver();
     let mut vade = Vade::new();
     vade.register_did_resolver(Box::from(rde));
 
@@ -60,9 +82,41 @@ async fn can_fetch_a_did_document() {
     assert!(&EXAMPLE_DID == &parsed["id"]);
 }
 
+#[tokio::test]
+async fn can_cache_did_documents() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = FixtureTransport::new()
+        .with_response(
+            "GET",
+            &format!("https://testcore.evan.network/did/{}", EXAMPLE_DID),
+            &format!(r#"{{"status":"success","did":{}}}"#, EXAMPLE_DID_DOCUMENT_STR),
+        );
+    let cache = LruCache::new(100, Duration::minutes(5));
+    let mut rde = RustDidResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(transport.clone()));
+    rde.did_cache = Some(cache.clone());
+
+    let fetched = rde.get_did_document(EXAMPLE_DID).await?;
+    let cached = rde.get_did_document(EXAMPLE_DID).await?;
+    assert_eq!(fetched, cached);
+    assert_eq!(transport.requests().len(), 1);
+    assert_eq!(cache.stats().hits, 1);
+    assert_eq!(cache.stats().misses, 1);
+
+    // invalidated documents are fetched again
+    assert!(cache.invalidate(EXAMPLE_DID));
+    rde.get_did_document(EXAMPLE_DID).await?;
+    assert_eq!(transport.requests().len(), 2);
+
+    // failed requests are not cached
+    assert!(rde.get_did_document("did:evan:testcore:0x1234").await.is_err());
+    assert!(rde.get_did_document("did:evan:testcore:0x1234").await.is_err());
+    assert_eq!(transport.requests().len(), 4);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn returns_an_error_for_invalid_did_ids() {
-    let rde = RustDidResolverEvan::new();
+    let rde = get_did_resolver();
     let mut vade = Vade::new();
     vade.register_did_resolver(Box::from(rde));
 
@@ -78,7 +132,8 @@ async fn returns_an_error_for_invalid_did_ids() {
 #[tokio::test]
 async fn can_handle_racing_resolvers_1() {
     let mut vade = Vade::new();
-    let rde = RustDidResolverEvan::new();
+    // node without fixtures, so only the cache can answer
+    let rde = RustDidResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(FixtureTransport::new()));
     vade.register_did_resolver(Box::from(rde));
     let mut storage = RustStorageCache::new();
 
@@ -95,7 +150,7 @@ async fn can_handle_racing_resolvers_1() {
 #[tokio::test]
 async fn can_handle_racing_resolvers_2() {
     let mut vade = Vade::new();
-    let rde = RustDidResolverEvan::new();
+    let rde = get_did_resolver();
     vade.register_did_resolver(Box::from(rde));
     let mut storage = RustStorageCache::new();
 
@@ -112,11 +167,11 @@ async fn can_handle_racing_resolvers_2() {
 }
 
 #[allow(dead_code)]
-// currently diabled as `RustDidResolverEvan` does not implement `set_vc_document` atm
+// currently diabled as `RustDidResolverEvan` needs a `signer` and a writable node for `set_did_document`
 // #[tokio::test]
 async fn can_handle_racing_resolvers_3() {
     let mut vade = Vade::new();
-    let rde = RustDidResolverEvan::new();
+    let rde = get_did_resolver();
     vade.register_did_resolver(Box::from(rde));
     let storage = RustStorageCache::new();
 
@@ -129,4 +184,259 @@ async fn can_handle_racing_resolvers_3() {
     let did = vade.get_did_document(&EXAMPLE_DID).await.unwrap();
     println!("{:?}", &did);
     assert!(did == String::from(EXAMPLE_DID_DOCUMENT_STR));
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn can_check_valid_did_documents() {
+    let rde = RustDidResolverEvan::new();
+    let mut vade = Vade::new();
+    vade.register_did_resolver(Box::from(rde));
+
+    match vade.check_did(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await {
+        Ok(()) => (),
+        Err(e) => panic!(format!("{}", e)),
+    };
+}
+
+#[tokio::test]
+async fn check_did_rejects_invalid_did_documents() {
+    let rde = RustDidResolverEvan::new();
+    let document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR).unwrap();
+
+    // not a json document
+    assert!(rde.check_did(EXAMPLE_DID, "qwer").await.is_err());
+
+    // other DID
+    let other_did = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f";
+    let result = rde.check_did(other_did, EXAMPLE_DID_DOCUMENT_STR).await;
+    assert!(format!("{}", result.unwrap_err()).contains("does not match DID"));
+
+    // missing context
+    let mut invalid = document.clone();
+    invalid["@context"] = Value::from("https://www.w3.org/2018/credentials/v1");
+    assert!(rde.check_did(EXAMPLE_DID, &invalid.to_string()).await.is_err());
+
+    // invalid ethereumAddress
+    let mut invalid = document.clone();
+    invalid["publicKey"][0]["ethereumAddress"] = Value::from("0xcd5e1dbb");
+    let result = rde.check_did(EXAMPLE_DID, &invalid.to_string()).await;
+    assert!(format!("{}", result.unwrap_err()).contains("invalid \"ethereumAddress\""));
+
+    // invalid controller
+    let mut invalid = document.clone();
+    invalid["publicKey"][0]["controller"] = Value::from("0x0d87204c3957d73b68ae28d0af961d3c72403906");
+    assert!(rde.check_did(EXAMPLE_DID, &invalid.to_string()).await.is_err());
+
+    // unknown authentication reference
+    let mut invalid = document.clone();
+    invalid["authentication"][0] = Value::from(format!("{}#key-2", EXAMPLE_DID));
+    let result = rde.check_did(EXAMPLE_DID, &invalid.to_string()).await;
+    assert!(format!("{}", result.unwrap_err()).contains("references unknown key"));
+}
+
+#[tokio::test]
+async fn check_did_accepts_public_key_hex_and_relative_key_ids() {
+    let rde = RustDidResolverEvan::new();
+    let mut document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR).unwrap();
+    document["publicKey"][0]["id"] = Value::from("#key-1");
+    document["publicKey"][0]["ethereumAddress"] = Value::Null;
+    document["publicKey"][0]["publicKeyHex"] = Value::from(
+        "02b97c30de767f084ce3080168ee293053ba33b235d7116a3263d29f1450936b71");
+
+    match rde.check_did(EXAMPLE_DID, &document.to_string()).await {
+        Ok(()) => (),
+        Err(e) => panic!(format!("{}", e)),
+    };
+}
+
+#[tokio::test]
+async fn check_did_accepts_jwk_and_base58_keys() {
+    let rde = RustDidResolverEvan::new();
+    let mut document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR).unwrap();
+    document["publicKey"][0]["ethereumAddress"] = Value::Null;
+
+    let mut with_jwk = document.clone();
+    with_jwk["publicKey"][0]["publicKeyJwk"] = serde_json::json!({
+        "kty": "EC",
+        "crv": "secp256k1",
+        "x": "Wt_VAsC8VfT8uQ7qNjaNfhnFswRapvUd-jaZBG6XUSU",
+        "y": "HSG8a90Gwf8AFPy7-fHYPHFENPKzPXE6r0Z2Dy1T8Q0",
+    });
+    assert!(rde.check_did(EXAMPLE_DID, &with_jwk.to_string()).await.is_ok());
+    with_jwk["publicKey"][0]["publicKeyJwk"]["crv"] = Value::from("P-256");
+    assert!(rde.check_did(EXAMPLE_DID, &with_jwk.to_string()).await.is_err());
+
+    let mut with_base58 = document.clone();
+    with_base58["publicKey"][0]["publicKeyBase58"] = Value::from("zogmFPqKrYgNogxk1XKMsoG45JtPDw3oynNS7eM583SU");
+    assert!(rde.check_did(EXAMPLE_DID, &with_base58.to_string()).await.is_ok());
+    with_base58["publicKey"][0]["publicKeyBase58"] = Value::from("0OIl");
+    assert!(rde.check_did(EXAMPLE_DID, &with_base58.to_string()).await.is_err());
+
+    // keys without key material are rejected
+    assert!(rde.check_did(EXAMPLE_DID, &document.to_string()).await.is_err());
+}
+
+#[tokio::test]
+async fn check_did_is_not_responsible_for_other_did_methods() {
+    let rde = RustDidResolverEvan::new();
+
+    assert!(rde.check_did("did:example:123", EXAMPLE_DID_DOCUMENT_STR).await.is_err());
+}
+
+#[tokio::test]
+async fn can_set_did_documents() {
+    // key and DID document of an identity with address 0x001de828935e8c7e4cb56fe610495cae63fb2612
+    let did = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f";
+    let did_document = r###"
+    {
+        "@context": "https://w3id.org/did/v1",
+        "id": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f",
+        "publicKey": [{
+            "id": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1",
+            "type": "Secp256k1VerificationKey2018",
+            "controller": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f",
+            "ethereumAddress": "0x001de828935e8c7e4cb56fe610495cae63fb2612"
+        }],
+        "authentication": ["did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1"]
+    }
+    "###;
+    let mut routes = HashMap::new();
+    routes.insert(format!("POST /did/{}", did), r#"{"status":"success"}"#.to_string());
+    let server = MockServer::start(routes);
+
+    let mut rde = RustDidResolverEvan::new_with_config(
+        ResolverConfig::new().with_write_endpoint("testcore", &server.url));
+    rde.signer = Some(Box::new(InMemorySigner::from_hex(
+        &format!("{}#key-1", did), "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a").unwrap()));
+
+    match rde.set_did_document(did, did_document).await {
+        Ok(()) => (),
+        Err(e) => panic!(format!("{}", e)),
+    };
+
+    let requests = server.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let sent: Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(sent["did"]["id"], did);
+    let (address, payload) = recover_address_and_data(sent["did"]["proof"]["jws"].as_str().unwrap()).unwrap();
+    assert_eq!(address, "001de828935e8c7e4cb56fe610495cae63fb2612");
+    let payload: Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(payload["didDocument"]["id"], did);
+    assert_eq!(payload["iss"], did);
+}
+
+#[tokio::test]
+async fn cannot_set_did_documents_without_signer() {
+    let mut rde = RustDidResolverEvan::new();
+
+    match rde.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await {
+        Ok(()) => panic!("did document set without signer"),
+        Err(e) => assert!(format!("{}", e).contains("no signer set")),
+    };
+}
+
+#[tokio::test]
+async fn cannot_set_did_documents_without_write_endpoint() {
+    let mut rde = RustDidResolverEvan::new();
+    rde.signer = Some(Box::new(InMemorySigner::from_hex(
+        &format!("{}#key-1", EXAMPLE_DID), "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a").unwrap()));
+
+    match rde.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await {
+        Ok(()) => panic!("did document set without write endpoint"),
+        Err(e) => assert_eq!(e, VadeEvanError::Config("no write endpoint configured for network \"testcore\"".to_string())),
+    };
+}
+
+#[tokio::test]
+async fn only_sets_did_documents_with_authentication_keys() {
+    // DID controlled by did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f, that
+    // authenticates with key 0x001de828935e8c7e4cb56fe610495cae63fb2612
+    let did = "did:evan:testcore:0x1234567890123456789012345678901234567890";
+    let controller = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f";
+    let did_document = r###"
+    {
+        "@context": "https://w3id.org/did/v1",
+        "id": "did:evan:testcore:0x1234567890123456789012345678901234567890",
+        "controller": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f",
+        "publicKey": []
+    }
+    "###;
+    let controller_document = r###"
+    {
+        "@context": "https://w3id.org/did/v1",
+        "id": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f",
+        "publicKey": [{
+            "id": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1",
+            "type": "Secp256k1VerificationKey2018",
+            "controller": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f",
+            "ethereumAddress": "0x001de828935e8c7e4cb56fe610495cae63fb2612"
+        }],
+        "authentication": ["#key-1"]
+    }
+    "###;
+    let transport = FixtureTransport::new()
+        .with_response(
+            "GET",
+            &format!("https://testcore.evan.network/did/{}", controller),
+            &format!(r#"{{"status":"success","did":{}}}"#, controller_document),
+        )
+        .with_response("POST", &format!("http://localhost:8080/did/{}", did), r#"{"status":"success"}"#);
+    let config = ResolverConfig::new().with_write_endpoint("testcore", "http://localhost:8080/");
+    let mut rde = RustDidResolverEvan::new_with_transport(config, Box::new(transport.clone()));
+
+    // keys of the DID's controller may set its document
+    rde.signer = Some(Box::new(InMemorySigner::from_hex(
+        &format!("{}#key-1", controller), "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a").unwrap()));
+    match rde.set_did_document(did, did_document).await {
+        Ok(()) => (),
+        Err(e) => panic!(format!("{}", e)),
+    };
+    assert_eq!(transport.requests().last().unwrap().method, "POST");
+
+    // keys not listed in `authentication` may not
+    rde.signer = Some(Box::new(InMemorySigner::from_hex(
+        &format!("{}#key-2", controller), "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a").unwrap()));
+    let request_count = transport.requests().len();
+    match rde.set_did_document(did, did_document).await {
+        Ok(()) => panic!("did document set with unauthorized key"),
+        Err(e) => assert_eq!(e, VadeEvanError::KeyNotFound(format!(
+            "key {}#key-2 is not authorized for authentication by did {}", controller, did))),
+    };
+    assert!(transport.requests()[request_count..].iter().all(|request| request.method == "GET"));
+}
+
+#[tokio::test]
+async fn returns_an_error_if_node_rejects_did_documents() {
+    let server = MockServer::start(HashMap::new());
+    let mut rde = RustDidResolverEvan::new_with_config(
+        ResolverConfig::new().with_write_endpoint("testcore", &server.url));
+    rde.signer = Some(Box::new(InMemorySigner::from_hex(
+        &format!("{}#key-1", EXAMPLE_DID), "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a").unwrap()));
+
+    match rde.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await {
+        Ok(()) => panic!("unexpected success"),
+        Err(e) => assert_eq!(format!("{}", e), "could not set did document, \"not found\""),
+    };
+}
+
+#[tokio::test]
+async fn returns_errors_for_malformed_responses() {
+    let mut routes = HashMap::new();
+    routes.insert(format!("GET /did/{}", EXAMPLE_DID), "<html>not json</html>".to_string());
+    let server = MockServer::start(routes);
+    let rde = RustDidResolverEvan::new_with_config(
+        ResolverConfig::new().with_endpoint("testcore", &server.url));
+
+    match rde.get_did_document(EXAMPLE_DID).await {
+        Ok(_) => panic!("unexpected did document"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Parse(_))),
+    };
+    match rde.get_did_document("did:evan:testcore:0x1234").await {
+        Ok(_) => panic!("unexpected did document"),
+        Err(e) => assert_eq!(e, VadeEvanError::Network("could not get did document, \"not found\"".to_string())),
+    };
+    match rde.check_did(EXAMPLE_DID, r#"{ "id": 42 }"#).await {
+        Ok(_) => panic!("unexpected success"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Schema(_))),
+    };
+}
@@ -0,0 +1,60 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+use chrono::{ DateTime, Duration, Utc };
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::nonce_store::{ FileNonceStore, InMemoryNonceStore, NonceStore };
+
+fn date(date: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc)
+}
+
+fn assert_rejects_replays_until_expiry(store: &dyn NonceStore) -> Result<(), VadeEvanError> {
+    let now = date("2020-05-01T12:00:00Z");
+    assert!(store.use_nonce("nonce-1", &now)?);
+    assert!(store.use_nonce("nonce-2", &now)?);
+    assert!(!store.use_nonce("nonce-1", &(now + Duration::minutes(59)))?);
+    assert!(store.use_nonce("nonce-1", &(now + Duration::minutes(61)))?);
+
+    Ok(())
+}
+
+#[test]
+fn in_memory_store_rejects_replays_until_expiry() -> Result<(), VadeEvanError> {
+    assert_rejects_replays_until_expiry(&InMemoryNonceStore::new(Duration::hours(1)))
+}
+
+#[test]
+fn file_store_rejects_replays_across_instances() -> Result<(), VadeEvanError> {
+    let path = std::env::temp_dir().join("vade-evan-nonce-store.json");
+    let _ = std::fs::remove_file(&path);
+    let path = path.to_str().unwrap();
+    assert_rejects_replays_until_expiry(&FileNonceStore::new(path, Duration::hours(1)))?;
+
+    // nonces are remembered by other instances using the same file
+    let other = FileNonceStore::new(path, Duration::hours(1));
+    assert!(!other.use_nonce("nonce-1", &date("2020-05-01T13:30:00Z"))?);
+    assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+    std::fs::remove_file(path).unwrap();
+
+    std::fs::write(path, "[]").unwrap();
+    assert!(matches!(other.use_nonce("nonce-1", &Utc::now()), Err(VadeEvanError::Schema(_))));
+    std::fs::remove_file(path).unwrap();
+
+    Ok(())
+}
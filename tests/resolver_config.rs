@@ -0,0 +1,89 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+use vade_evan::plugin::rust_didresolver_evan::RustDidResolverEvan;
+use vade_evan::plugin::rust_vcresolver_evan::RustVcResolverEvan;
+use vade_evan::utils::config::ResolverConfig;
+
+#[test]
+fn can_parse_network_from_ids() {
+    assert_eq!(ResolverConfig::get_network("did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906").unwrap(), Some("testcore".to_string()));
+    assert_eq!(ResolverConfig::get_network("did:evan:0x0d87204c3957d73b68ae28d0af961d3c72403906").unwrap(), None);
+    assert_eq!(ResolverConfig::get_network("vc:evan:mynet:0x75956ef9").unwrap(), Some("mynet".to_string()));
+    assert!(ResolverConfig::get_network("did:example:123").is_err());
+    assert!(ResolverConfig::get_network("something different").is_err());
+}
+
+#[test]
+fn uses_default_endpoints() {
+    let config = ResolverConfig::new();
+
+    assert_eq!(
+        config.get_url("did", "did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906").unwrap(),
+        "https://testcore.evan.network/did/did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906",
+    );
+    assert_eq!(
+        config.get_url("vc", "vc:evan:core:0x75956ef9").unwrap(),
+        "https://core.evan.network/vc/vc:evan:core:0x75956ef9",
+    );
+}
+
+#[test]
+fn resolves_ids_without_network_on_testcore_by_default() {
+    let id = "did:evan:0x0d87204c3957d73b68ae28d0af961d3c72403906";
+    assert_eq!(
+        ResolverConfig::new().get_url("did", id).unwrap(),
+        "https://testcore.evan.network/did/did:evan:0x0d87204c3957d73b68ae28d0af961d3c72403906",
+    );
+    assert_eq!(
+        ResolverConfig::new().with_default_network("core").get_url("did", id).unwrap(),
+        "https://core.evan.network/did/did:evan:0x0d87204c3957d73b68ae28d0af961d3c72403906",
+    );
+}
+
+#[test]
+fn can_use_custom_endpoints() {
+    let config = ResolverConfig::new()
+        .with_endpoint("testcore", "http://localhost:8080")
+        .with_endpoint("mynet", "http://localhost:8081/");
+
+    assert_eq!(
+        config.get_url("did", "did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906").unwrap(),
+        "http://localhost:8080/did/did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906",
+    );
+    assert_eq!(
+        config.get_url("vc", "vc:evan:mynet:0x75956ef9").unwrap(),
+        "http://localhost:8081/vc/vc:evan:mynet:0x75956ef9",
+    );
+    assert!(config.get_url("vc", "vc:evan:othernet:0x75956ef9").is_err());
+}
+
+#[tokio::test]
+async fn resolvers_return_an_error_for_unconfigured_networks() {
+    let rde = RustDidResolverEvan::new_with_config(ResolverConfig::empty());
+    match rde.get_did_document("did:evan:testcore:0x0d87204c3957d73b68ae28d0af961d3c72403906").await {
+        Ok(_) => panic!("unexpected did document"),
+        Err(e) => assert_eq!(format!("{}", e), "no endpoint configured for network \"testcore\""),
+    }
+
+    let vcr = RustVcResolverEvan::new_with_config(ResolverConfig::empty());
+    match vcr.get_vc_document("vc:evan:core:0x75956ef9").await {
+        Ok(_) => panic!("unexpected vc document"),
+        Err(e) => assert_eq!(format!("{}", e), "no endpoint configured for network \"core\""),
+    }
+}
@@ -0,0 +1,158 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+use chrono::Utc;
+use ed25519_dalek::Signature;
+use std::convert::TryFrom;
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::signer::{ Ed25519Signer, InMemoryEd25519Signer, InMemorySigner, KeystoreSigner, Signer };
+use vade_evan::utils::signing::{ create_proof, sign_with_signer, verify_signature, JWS_ALG_ES256K_R };
+use vade_evan::utils::verification_key::VerificationKey;
+
+const EXAMPLE_KEY_ID: &str = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+const EXAMPLE_PRIVATE_KEY: &str = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+const EXAMPLE_ADDRESS: &str = "0x001de828935e8c7e4cb56fe610495cae63fb2612";
+
+#[tokio::test]
+async fn in_memory_signer_uses_given_key() -> Result<(), VadeEvanError> {
+    let signer = InMemorySigner::from_hex(EXAMPLE_KEY_ID, EXAMPLE_PRIVATE_KEY)?;
+    assert_eq!(signer.key_id(), EXAMPLE_KEY_ID);
+    assert_eq!(signer.address().await?, EXAMPLE_ADDRESS);
+
+    // signatures can be verified with the key's address
+    let message = "test message".as_bytes();
+    let signature = sign_with_signer(message, &signer).await?;
+    let key = VerificationKey::EthereumAddress(EXAMPLE_ADDRESS.to_string());
+    verify_signature(message, &signature[..64], &key)?;
+    assert!(verify_signature("other message".as_bytes(), &signature[..64], &key).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn in_memory_signer_rejects_invalid_keys() {
+    for key in &["", "0173", "not hex", &"00".repeat(32)] {
+        assert!(matches!(InMemorySigner::from_hex(EXAMPLE_KEY_ID, key), Err(VadeEvanError::Signature(_))));
+    }
+}
+
+#[tokio::test]
+async fn in_memory_ed25519_signer_uses_given_key() -> Result<(), VadeEvanError> {
+    let signer = InMemoryEd25519Signer::from_hex(EXAMPLE_KEY_ID, EXAMPLE_PRIVATE_KEY)?;
+    assert_eq!(signer.key_id(), EXAMPLE_KEY_ID);
+
+    // signatures can be verified with the key's public key
+    let message = "test message".as_bytes();
+    let signature = signer.sign(message).await?;
+    let public_key = signer.public_key().await?;
+    let signature = Signature::try_from(&signature[..]).unwrap();
+    assert!(public_key.verify_strict(message, &signature).is_ok());
+    assert!(public_key.verify_strict("other message".as_bytes(), &signature).is_err());
+
+    let generated = InMemoryEd25519Signer::generate(EXAMPLE_KEY_ID);
+    assert_ne!(generated.public_key().await?, signer.public_key().await?);
+    for key in &["", "0173", "not hex"] {
+        assert!(matches!(InMemoryEd25519Signer::from_hex(EXAMPLE_KEY_ID, key), Err(VadeEvanError::Signature(_))));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keystore_signer_can_be_saved_and_opened() -> Result<(), VadeEvanError> {
+    let path = std::env::temp_dir().join("vade-evan-keystore.json");
+    let path = path.to_str().unwrap();
+    let signer = InMemorySigner::from_hex(EXAMPLE_KEY_ID, EXAMPLE_PRIVATE_KEY)?;
+    KeystoreSigner::save(path, "secret", &signer, 10)?;
+
+    let keystore: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(keystore["version"], 3);
+    assert_eq!(keystore["address"], EXAMPLE_ADDRESS.trim_start_matches("0x"));
+    assert_eq!(keystore["crypto"]["kdf"], "scrypt");
+    assert_eq!(keystore["crypto"]["kdfparams"]["n"], 1024);
+    assert_eq!(keystore["crypto"]["cipher"], "aes-128-ctr");
+    assert!(!keystore.to_string().contains(EXAMPLE_PRIVATE_KEY));
+
+    let opened = KeystoreSigner::open(path, EXAMPLE_KEY_ID, "secret")?;
+    assert_eq!(opened.key_id(), EXAMPLE_KEY_ID);
+    assert_eq!(opened.address().await?, EXAMPLE_ADDRESS);
+    let digest = [7u8; 32];
+    assert_eq!(opened.sign_digest(&digest).await?.to_vec(), signer.sign_digest(&digest).await?.to_vec());
+
+    assert!(matches!(KeystoreSigner::open(path, EXAMPLE_KEY_ID, "wrong"), Err(VadeEvanError::Signature(_))));
+
+    // opened keystores can be saved with another password
+    KeystoreSigner::save(path, "other secret", &opened, 10)?;
+    let reopened = KeystoreSigner::open(path, EXAMPLE_KEY_ID, "other secret")?;
+    assert_eq!(reopened.address().await?, EXAMPLE_ADDRESS);
+    std::fs::remove_file(path).unwrap();
+    assert!(matches!(KeystoreSigner::open(path, EXAMPLE_KEY_ID, "secret"), Err(VadeEvanError::Config(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keystore_signer_can_open_web3_secret_storage_files() -> Result<(), VadeEvanError> {
+    // PBKDF2 test vector from the Web3 Secret Storage definition
+    let path = std::env::temp_dir().join("vade-evan-keystore-pbkdf2.json");
+    let path = path.to_str().unwrap();
+    let mut keystore = serde_json::json!({
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd",
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2",
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3,
+    });
+    std::fs::write(path, keystore.to_string()).unwrap();
+    let opened = KeystoreSigner::open(path, EXAMPLE_KEY_ID, "testpassword")?;
+    let expected = InMemorySigner::from_hex(
+        EXAMPLE_KEY_ID, "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d")?;
+    assert_eq!(opened.address().await?, expected.address().await?);
+
+    keystore["crypto"]["cipher"] = serde_json::Value::from("aes-128-cbc");
+    std::fs::write(path, keystore.to_string()).unwrap();
+    assert!(matches!(KeystoreSigner::open(path, EXAMPLE_KEY_ID, "testpassword"), Err(VadeEvanError::Unsupported(_))));
+    keystore["version"] = serde_json::Value::from(1);
+    std::fs::write(path, keystore.to_string()).unwrap();
+    assert!(matches!(KeystoreSigner::open(path, EXAMPLE_KEY_ID, "testpassword"), Err(VadeEvanError::Unsupported(_))));
+    std::fs::remove_file(path).unwrap();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn proofs_contain_key_ids_verbatim() -> Result<(), VadeEvanError> {
+    let key_id = r#"did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-"1\"#;
+    let signer = InMemorySigner::from_hex(key_id, EXAMPLE_PRIVATE_KEY)?;
+    let document = serde_json::json!({ "id": "foo" });
+    let proof = create_proof(&document, "vc", "did:evan:testcore:0x0", &signer, JWS_ALG_ES256K_R, None, &Utc::now()).await?;
+    assert_eq!(proof["verificationMethod"], key_id);
+    assert_eq!(proof.as_object().unwrap().len(), 5);
+
+    Ok(())
+}
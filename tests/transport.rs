@@ -0,0 +1,83 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+mod common;
+
+use common::MockServer;
+use std::collections::HashMap;
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::transport::{ FixtureTransport, ReqwestTransport, Transport, TransportRequest };
+
+#[tokio::test]
+async fn fixture_transport_answers_with_registered_responses() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = FixtureTransport::new()
+        .with_response("GET", "https://example.org/a", "a")
+        .with_response("POST", "https://example.org/b", "b");
+    let clone = transport.clone();
+
+    assert_eq!(clone.get("https://example.org/a").await?, "a");
+    assert_eq!(clone.post("https://example.org/b", "{}").await?, "b");
+    match clone.get("https://example.org/b").await {
+        Ok(_) => panic!("unexpected response"),
+        Err(e) => assert_eq!(e, VadeEvanError::Network("no fixture for \"GET https://example.org/b\"".to_string())),
+    };
+
+    assert_eq!(transport.requests(), vec![
+        TransportRequest { method: "GET".to_string(), url: "https://example.org/a".to_string(), body: None },
+        TransportRequest { method: "POST".to_string(), url: "https://example.org/b".to_string(), body: Some("{}".to_string()) },
+        TransportRequest { method: "GET".to_string(), url: "https://example.org/b".to_string(), body: None },
+    ]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reqwest_transport_sends_configured_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut routes = HashMap::new();
+    routes.insert("GET /a".to_string(), r#"{"status":"success"}"#.to_string());
+    routes.insert("POST /b".to_string(), r#"{"status":"success"}"#.to_string());
+    let server = MockServer::start(routes);
+    let transport = ReqwestTransport::new()
+        .with_header("Authorization", "Bearer 1234");
+
+    assert_eq!(transport.get(&format!("{}/a", server.url)).await?, r#"{"status":"success"}"#);
+    assert_eq!(transport.post(&format!("{}/b", server.url), r#"{"foo":"bar"}"#).await?, r#"{"status":"success"}"#);
+
+    let requests = server.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].headers.get("authorization").map(|h| h.as_str()), Some("Bearer 1234"));
+    assert_eq!(requests[1].headers.get("authorization").map(|h| h.as_str()), Some("Bearer 1234"));
+    assert_eq!(requests[1].headers.get("content-type").map(|h| h.as_str()), Some("application/json"));
+    assert_eq!(requests[1].body, r#"{"foo":"bar"}"#);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reqwest_transport_rejects_non_success_responses() -> Result<(), Box<dyn std::error::Error>> {
+    let mut routes = HashMap::new();
+    routes.insert("GET /a".to_string(), (502, "<html>502 Bad Gateway</html>".to_string()));
+    routes.insert("POST /b".to_string(), (404, r#"{"status":"error"}"#.to_string()));
+    let server = MockServer::start_with_status(routes);
+    let transport = ReqwestTransport::new();
+
+    assert!(matches!(transport.get(&format!("{}/a", server.url)).await, Err(VadeEvanError::Network(_))));
+    assert!(matches!(transport.post(&format!("{}/b", server.url), "{}").await, Err(VadeEvanError::Network(_))));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::trusted_issuers::TrustedIssuerRegistry;
+
+const ISSUER: &str = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f";
+const OTHER_ISSUER: &str = "did:evan:testcore:0x67ced07dd4f37aa2319bedd97d040b64888c57bc";
+
+fn types(types: &[&str]) -> Vec<String> {
+    types.iter().map(|credential_type| credential_type.to_string()).collect()
+}
+
+#[test]
+fn trusts_issuers_per_credential_type() {
+    let registry = TrustedIssuerRegistry::new()
+        .with_issuer("SupplierCredential", ISSUER)
+        .with_issuer("VerifiableCredential", OTHER_ISSUER);
+
+    assert!(registry.is_trusted(ISSUER, &types(&["VerifiableCredential", "SupplierCredential"])));
+    assert!(!registry.is_trusted(ISSUER, &types(&["VerifiableCredential", "AuditorCredential"])));
+    assert!(registry.is_trusted(OTHER_ISSUER, &types(&["VerifiableCredential", "AuditorCredential"])));
+    assert!(!registry.is_trusted(ISSUER, &[]));
+
+    match registry.check(ISSUER, &types(&["AuditorCredential"])) {
+        Ok(_) => panic!("untrusted issuer accepted"),
+        Err(e) => assert_eq!(
+            e,
+            VadeEvanError::Policy(format!("issuer \"{}\" is not trusted to issue VCs of type \"AuditorCredential\"", ISSUER)),
+        ),
+    }
+}
+
+#[test]
+fn can_load_registries_from_json_files() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join("vade-evan-trusted-issuers.json");
+    std::fs::write(&path, format!(r#"{{ "SupplierCredential": [ "{}", "{}" ] }}"#, ISSUER, OTHER_ISSUER))?;
+    let registry = TrustedIssuerRegistry::from_file(path.to_str().unwrap())?;
+    std::fs::remove_file(&path)?;
+
+    assert!(registry.is_trusted(ISSUER, &types(&["SupplierCredential"])));
+    assert!(registry.is_trusted(OTHER_ISSUER, &types(&["SupplierCredential"])));
+    assert!(!registry.is_trusted(ISSUER, &types(&["VerifiableCredential"])));
+
+    Ok(())
+}
+
+#[test]
+fn returns_errors_for_invalid_registries() {
+    assert!(matches!(TrustedIssuerRegistry::from_json("qwer"), Err(VadeEvanError::Parse(_))));
+    assert!(matches!(TrustedIssuerRegistry::from_json("[]"), Err(VadeEvanError::Schema(_))));
+    assert!(matches!(TrustedIssuerRegistry::from_json(r#"{ "SupplierCredential": "did:evan:0x1" }"#), Err(VadeEvanError::Schema(_))));
+    assert!(matches!(TrustedIssuerRegistry::from_json(r#"{ "SupplierCredential": [ 1 ] }"#), Err(VadeEvanError::Schema(_))));
+    assert!(matches!(TrustedIssuerRegistry::from_file("/does/not/exist.json"), Err(VadeEvanError::Config(_))));
+}
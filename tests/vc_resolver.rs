@@ -19,15 +19,37 @@ extern crate sha3;
 extern crate vade;
 extern crate vade_evan;
 
+mod common;
+
+use common::MockServer;
+use chrono::{ DateTime, Duration, Utc };
+use data_encoding::BASE64URL_NOPAD;
 use serde_json::Value;
+use std::collections::HashMap;
 use vade::Vade;
 use vade::plugin::rust_storage_cache::RustStorageCache;
-use vade::traits::DidResolver;
+use vade::traits::{ DidResolver, VcResolver };
+use vade_evan::plugin::rust_didresolver_evan::RustDidResolverEvan;
 use vade_evan::plugin::rust_vcresolver_evan::{
+    CreateVcOptions,
+    MAX_CONTROLLER_DEPTH,
     RustVcResolverEvan,
     VC_DEFAULT_TYPE,
     VC_W3C_MANDATORY_CONTEXT,
 };
+use vade_evan::utils::cache::LruCache;
+use vade_evan::utils::clock::FixedClock;
+use vade_evan::utils::config::ResolverConfig;
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::nonce_store::InMemoryNonceStore;
+use vade_evan::utils::signer::{ InMemoryEd25519Signer, InMemorySigner, KeystoreSigner, Signer };
+use vade_evan::utils::signing::{ create_jwt, recover_address_and_data, sign_with_signer, JWS_ALG_ES256K_R };
+use vade_evan::utils::status_cache::{ StatusCache, StatusCacheMode };
+use vade_evan::utils::transport::FixtureTransport;
+use vade_evan::utils::trusted_issuers::TrustedIssuerRegistry;
+use vade_evan::utils::vc_storage::{ EvanVcStorage, InMemoryVcStorage };
+use vade_evan::utils::verification_policy::VerificationPolicy;
+use vade_evan::utils::verification_report::{ CheckStatus, CheckType, KeyAuthorization };
 
 const EXAMPLE_VC_NAME_REMOTE: &str = "vc:evan:testcore:0x75956ef9b3ea7d7230cf007b8ee042bcaa2a4dad8c043fa77ecf51262ee4f7a9";
 const EXAMPLE_VC_DOCUMENT_STR_REMOTE: &str = r###"
@@ -136,9 +158,44 @@ const EXAMPLE_DID_DOCUMENT_STR: &str = r###"
    [ "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1" ] }
 "###;
 
+/// Parses RFC 3339 date string.
+fn date(date: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc)
+}
+
+/// Creates transport that answers requests for example VCs and their status.
+fn get_fixture_transport() -> FixtureTransport {
+    FixtureTransport::new()
+        .with_response(
+            "GET",
+            &format!("https://testcore.evan.network/vc/{}", EXAMPLE_VC_NAME_REMOTE),
+            &format!(r#"{{"status":"success","vc":{}}}"#, EXAMPLE_VC_DOCUMENT_STR_REMOTE),
+        )
+        .with_response(
+            "GET",
+            &format!("https://testcore.evan.network/vc/status/{}", EXAMPLE_VC_NAME),
+            r#"{"status":"success","vcStatus":"active"}"#,
+        )
+}
+
+/// Encodes data as base64url without padding.
+fn base64url(data: &[u8]) -> String {
+    BASE64URL_NOPAD.encode(data)
+}
+
+/// Creates signer for a secp256k1 key given as hex string.
+fn get_signer(key_id: &str, private_key: &str) -> InMemorySigner {
+    InMemorySigner::from_hex(key_id, private_key).unwrap()
+}
+
+/// Creates resolver that answers requests from fixtures.
+fn get_vc_resolver() -> RustVcResolverEvan {
+    RustVcResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(get_fixture_transport()))
+}
+
 #[tokio::test]
 async fn can_fetch_a_vc_document() {
-    let rde = RustVcResolverEvan::new();
+    let rde = get_vc_resolver();
     let mut vade = Vade::new();
     vade.register_vc_resolver(Box::from(rde));
 
@@ -152,7 +209,7 @@ async fn can_fetch_a_vc_document() {
 
 #[tokio::test]
 async fn returns_an_error_for_invalid_vc_ids() {
-    let rde = RustVcResolverEvan::new();
+    let rde = get_vc_resolver();
     let mut vade = Vade::new();
     vade.register_vc_resolver(Box::from(rde));
 
@@ -168,7 +225,7 @@ async fn returns_an_error_for_invalid_vc_ids() {
 #[tokio::test]
 async fn can_handle_racing_resolvers_1() {
     let mut vade = Vade::new();
-    let rde = RustVcResolverEvan::new();
+    let rde = get_vc_resolver();
     vade.register_vc_resolver(Box::from(rde));
     let mut storage = RustStorageCache::new();
 
@@ -185,7 +242,7 @@ async fn can_handle_racing_resolvers_1() {
 #[tokio::test]
 async fn can_handle_racing_resolvers_2() {
     let mut vade = Vade::new();
-    let rde = RustVcResolverEvan::new();
+    let rde = get_vc_resolver();
     vade.register_vc_resolver(Box::from(rde));
     let mut storage = RustStorageCache::new();
 
@@ -207,7 +264,7 @@ async fn can_handle_racing_resolvers_2() {
 async fn can_handle_racing_resolvers_3() {
     let mut vade = Vade::new();
 
-    let mut rde = RustVcResolverEvan::new();
+    let mut rde = get_vc_resolver();
     let mut rde_did_resolver = RustStorageCache::new();
     match rde_did_resolver.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await {
         Ok(()) => (),
@@ -240,7 +297,7 @@ async fn can_validate_valid_vcs() -> std::result::Result<(), Box<dyn std::error:
     vcr_vade.register_did_resolver(Box::from(vcr_didr));
     // add did document to vcr's did resolver
     vcr_vade.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await?;
-    let mut vcr = RustVcResolverEvan::new();
+    let mut vcr = get_vc_resolver();
     vcr.vade = Some(Box::from(vcr_vade));
 
     // create vade to work with, attach 
@@ -265,7 +322,7 @@ async fn cannot_validate_invalid_vcs() -> std::result::Result<(), Box<dyn std::e
     vcr_vade.register_did_resolver(Box::from(vcr_didr));
     // add did document to vcr's did resolver
     vcr_vade.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await?;
-    let mut vcr = RustVcResolverEvan::new();
+    let mut vcr = get_vc_resolver();
     vcr.vade = Some(Box::from(vcr_vade));
 
     // create vade to work with, attach 
@@ -302,7 +359,7 @@ async fn vc_resolver_can_create_new_vcs() -> std::result::Result<(), Box<dyn std
 
     let vcr = RustVcResolverEvan::new();
 
-    let vc: String = vcr.create_vc(partial_vc_data, &veri_method, &veri_pkey).await.unwrap();
+    let vc: String = vcr.create_vc_with_signer(partial_vc_data, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await.unwrap();
 
     let parsed: Value = serde_json::from_str(&vc).unwrap();
 
@@ -334,7 +391,7 @@ async fn vc_resolver_can_create_new_vcs_and_validate_them(
 
     let mut vcr = RustVcResolverEvan::new();
 
-    let vc: String = vcr.create_vc(partial_vc_data, &veri_method, &veri_pkey).await.unwrap();
+    let vc: String = vcr.create_vc_with_signer(partial_vc_data, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await.unwrap();
 
     let parsed: Value = serde_json::from_str(&vc).unwrap();
 
@@ -386,7 +443,7 @@ async fn vc_resolver_can_create_new_vcs_and_fails_on_invalid_private_key(
 
     let mut vcr = RustVcResolverEvan::new();
 
-    let vc: String = vcr.create_vc(partial_vc_data, &veri_method, &veri_pkey).await.unwrap();
+    let vc: String = vcr.create_vc_with_signer(partial_vc_data, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await.unwrap();
 
     let parsed: Value = serde_json::from_str(&vc).unwrap();
 
@@ -420,3 +477,1306 @@ async fn vc_resolver_can_create_new_vcs_and_fails_on_invalid_private_key(
     Ok(())
 }
 
+/// Stores a DID document with `controller` and `authentication` in vade of given resolver.
+async fn set_did_document(
+    vcr: &mut RustVcResolverEvan,
+    did: &str,
+    controller: Option<&str>,
+    authentication: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut did_document = serde_json::json!({
+        "@context": "https://w3id.org/did/v1",
+        "id": did,
+        "publicKey": [],
+        "authentication": authentication,
+    });
+    if let Some(controller) = controller {
+        did_document["controller"] = Value::from(controller);
+    }
+    vcr.vade.as_mut().unwrap().set_did_document(did, &did_document.to_string()).await?;
+
+    Ok(())
+}
+
+async fn get_vc_resolver_with_did() -> Result<RustVcResolverEvan, Box<dyn std::error::Error>> {
+    let vcr_didr = RustStorageCache::new();
+    let mut vcr_vade = Vade::new();
+    vcr_vade.register_did_resolver(Box::from(vcr_didr));
+    vcr_vade.set_did_document(EXAMPLE_DID, EXAMPLE_DID_DOCUMENT_STR).await?;
+    let mut vcr = get_vc_resolver();
+    vcr.vade = Some(Box::from(vcr_vade));
+
+    Ok(vcr)
+}
+
+#[tokio::test]
+async fn cannot_validate_revoked_vcs() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = get_fixture_transport();
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.transport = Box::new(transport.clone());
+
+    let status_url = format!("https://testcore.evan.network/vc/status/{}", EXAMPLE_VC_NAME);
+    transport.add_response("GET", &status_url, r#"{"status":"success","vcStatus":"revoked"}"#);
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("revoked VC document recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Revoked(_))),
+    }
+    assert_eq!(transport.requests().last().map(|r| r.url.to_string()), Some(status_url));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_get_detailed_verification_reports() -> Result<(), Box<dyn std::error::Error>> {
+    let vcr = get_vc_resolver_with_did().await?;
+
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await;
+    assert!(report.is_valid());
+    assert_eq!(report.vc_id, EXAMPLE_VC_NAME);
+    let checks: Vec<CheckType> = report.checks.iter().map(|result| result.check).collect();
+    assert_eq!(checks, vec![
+        CheckType::Schema,
+        CheckType::Expiry,
+        CheckType::TrustedIssuer,
+        CheckType::SignatureRecovery,
+        CheckType::DocumentEquality,
+        CheckType::KeyLookup,
+        CheckType::CredentialStatus,
+    ]);
+    assert!(matches!(report.get(CheckType::TrustedIssuer), Some(CheckStatus::Skipped(_))));
+    assert!(report.checks.iter()
+        .filter(|result| result.check != CheckType::TrustedIssuer)
+        .all(|result| result.status == CheckStatus::Passed));
+
+    // checking stops at first failed check
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_MANIPULATED_STR).await;
+    assert!(!report.is_valid());
+    assert_eq!(report.get(CheckType::SignatureRecovery), Some(&CheckStatus::Passed));
+    assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+    assert_eq!(report.get(CheckType::KeyLookup), None);
+    assert!(matches!(report.error(), Some(VadeEvanError::Signature(_))));
+
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, "qwer").await;
+    assert!(matches!(report.get(CheckType::Schema), Some(CheckStatus::Failed(VadeEvanError::Parse(_)))));
+    assert_eq!(report.checks.len(), 1);
+
+    // status check is skipped for VCs without credentialStatus
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid());
+    assert!(matches!(report.get(CheckType::CredentialStatus), Some(CheckStatus::Skipped(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_vcs_without_proof_unless_allowed_by_policy() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vc: Value = serde_json::from_str(EXAMPLE_VC_DOCUMENT_STR)?;
+    vc.as_object_mut().unwrap().remove("proof");
+    let unsigned = vc.to_string();
+    let mut vcr = get_vc_resolver_with_did().await?;
+
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, &unsigned).await;
+    assert!(matches!(report.get(CheckType::SignatureRecovery), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+    match vcr.check_vc(EXAMPLE_VC_NAME, &unsigned).await {
+        Ok(_) => panic!("unsigned VC document recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Signature(_))),
+    }
+
+    vcr.policy.allow_unsigned = true;
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, &unsigned).await;
+    assert!(report.is_valid());
+    assert!(matches!(report.get(CheckType::SignatureRecovery), Some(CheckStatus::Skipped(_))));
+    vcr.check_vc(EXAMPLE_VC_NAME, &unsigned).await?;
+
+    // signed VCs are still checked
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_MANIPULATED_STR).await {
+        Ok(_) => panic!("manipulated VC document recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Signature(_))),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_vcs_signed_by_other_dids_than_issuer() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let forged_issuer = "did:evan:testcore:0x67ced07dd4f37aa2319bedd97d040b64888c57bc";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    set_did_document(&mut vcr, forged_issuer, None, &[]).await?;
+
+    for issuer in &[format!(r#""{}""#, forged_issuer), format!(r#"{{ "id": "{}" }}"#, forged_issuer)] {
+        let forged = vcr.create_vc_with_signer(&format!(r#"{{ "id": "foo", "issuer": {} }}"#, issuer), &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+        let report = vcr.verify_vc_detailed("foo", &forged).await;
+        assert_eq!(report.get(CheckType::DocumentEquality), Some(&CheckStatus::Passed));
+        match report.error() {
+            Some(e) => assert_eq!(e, &VadeEvanError::Signature(format!(
+                "verificationMethod \"{}\" does not belong to issuer \"{}\"", veri_method, forged_issuer))),
+            None => panic!("VC with forged issuer recognized as valid"),
+        }
+        assert!(matches!(report.get(CheckType::KeyLookup), Some(CheckStatus::Failed(_))));
+    }
+
+    // issuer given as object is bound to verification method as well
+    let vc = vcr.create_vc_with_signer(&format!(r#"{{ "id": "foo", "issuer": {{ "id": "{}" }} }}"#, EXAMPLE_DID), &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    vcr.check_vc("foo", &vc).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_check_vcs_with_public_keys_instead_of_addresses() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let key_formats = vec![
+        ("publicKeyHex", Value::from("035adfd502c0bc55f4fcb90eea36368d7e19c5b3045aa6f51dfa3699046e975125")),
+        ("publicKeyBase58", Value::from("zogmFPqKrYgNogxk1XKMsoG45JtPDw3oynNS7eM583SU")),
+        ("publicKeyJwk", serde_json::json!({
+            "kty": "EC",
+            "crv": "secp256k1",
+            "x": "Wt_VAsC8VfT8uQ7qNjaNfhnFswRapvUd-jaZBG6XUSU",
+            "y": "HSG8a90Gwf8AFPy7-fHYPHFENPKzPXE6r0Z2Dy1T8Q0",
+        })),
+    ];
+    for (property, key) in key_formats {
+        let mut did_document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR)?;
+        did_document["publicKey"][0].as_object_mut().unwrap().remove("ethereumAddress");
+        did_document["publicKey"][0][property] = key;
+        vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+        vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+        match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_MANIPULATED_STR).await {
+            Ok(_) => panic!("manipulated VC document recognized as valid"),
+            Err(e) => assert!(matches!(e, VadeEvanError::Signature(_))),
+        }
+    }
+
+    // keys of other identities are not accepted
+    let mut did_document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR)?;
+    did_document["publicKey"][0].as_object_mut().unwrap().remove("ethereumAddress");
+    did_document["publicKey"][0]["publicKeyHex"] = Value::from("02b97c30de767f084ce3080168ee293053ba33b235d7116a3263d29f1450936b71");
+    vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("VC document with key of other identity recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Signature(_))),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_create_and_check_ed25519_signed_vcs() -> Result<(), Box<dyn std::error::Error>> {
+    // key pair from RFC 8032, test 1
+    let veri_pkey = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    let public_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    let issuer = "did:evan:testcore:0x1000000000000000000000000000000000000003";
+    let veri_method = format!("{}#key-1", issuer);
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let did_document = serde_json::json!({
+        "@context": "https://w3id.org/did/v1",
+        "id": issuer,
+        "publicKey": [{
+            "id": veri_method,
+            "type": "Ed25519VerificationKey2018",
+            "controller": issuer,
+            "publicKeyHex": public_key,
+        }],
+        "assertionMethod": [ veri_method ],
+    });
+    vcr.vade.as_mut().unwrap().set_did_document(issuer, &did_document.to_string()).await?;
+
+    let mut options = CreateVcOptions::new();
+    options.proof_type = "Ed25519Signature2018".to_string();
+    let signer = InMemoryEd25519Signer::from_hex(&veri_method, veri_pkey)?;
+    let vc = vcr.create_vc_with_ed25519_signer(r#"{ "id": "foo" }"#, &signer, &options).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    assert_eq!(parsed["proof"]["type"], "Ed25519Signature2018");
+    assert!(parsed["proof"]["jws"].as_str().unwrap().contains(".."));
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid(), "unexpected error {:?}", report.error());
+    assert_eq!(report.get(CheckType::KeyLookup), Some(&CheckStatus::Passed));
+    assert_eq!(report.get(CheckType::SignatureVerification), Some(&CheckStatus::Passed));
+    assert_eq!(report.get(CheckType::SignatureRecovery), None);
+
+    // manipulated documents and proofs are rejected
+    let mut manipulated = parsed.clone();
+    manipulated["credentialSubject"] = serde_json::json!({ "id": "did:evan:testcore:0x0" });
+    let mut manipulated_proof = parsed.clone();
+    manipulated_proof["proof"]["created"] = Value::from("2020-01-01T00:00:00.000Z");
+    for vc in &[manipulated, manipulated_proof] {
+        let report = vcr.verify_vc_detailed("foo", &vc.to_string()).await;
+        assert!(matches!(report.get(CheckType::SignatureVerification), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+    }
+
+    // Ed25519 proofs cannot be checked with secp256k1 keys
+    let vc = vcr.create_vc_with_ed25519_signer(
+        r#"{ "id": "foo" }"#, &InMemoryEd25519Signer::from_hex(&format!("{}#key-1", EXAMPLE_DID), veri_pkey)?, &options).await?;
+    assert!(matches!(vcr.check_vc("foo", &vc).await, Err(VadeEvanError::Signature(_))));
+
+    // unknown proof types can neither be created nor checked
+    options.proof_type = "RsaSignature2018".to_string();
+    let result = vcr.create_vc_with_ed25519_signer(r#"{ "id": "foo" }"#, &signer, &options).await;
+    assert!(matches!(result, Err(VadeEvanError::Unsupported(_))));
+    let mut unknown = parsed.clone();
+    unknown["proof"]["type"] = Value::from("RsaSignature2018");
+    vcr.policy.allowed_proof_types.push("RsaSignature2018".to_string());
+    assert!(matches!(vcr.check_vc("foo", &unknown.to_string()).await, Err(VadeEvanError::Unsupported(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_create_and_check_vcs_with_non_recoverable_signatures() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let mut options = CreateVcOptions::new();
+    options.jws_alg = "ES256K".to_string();
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &options).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    let jws = parsed["proof"]["jws"].as_str().unwrap();
+    assert_eq!(jws.split('.').next(), Some("eyJ0eXAiOiJKV1QiLCJhbGciOiJFUzI1NksifQ"));
+    // 64B signatures without recovery id
+    assert_eq!(jws.rsplit('.').next().unwrap().len(), 86);
+
+    // signatures are verified with addresses and public keys
+    let mut with_public_key: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR)?;
+    with_public_key["publicKey"][0].as_object_mut().unwrap().remove("ethereumAddress");
+    with_public_key["publicKey"][0]["publicKeyHex"] = Value::from("035adfd502c0bc55f4fcb90eea36368d7e19c5b3045aa6f51dfa3699046e975125");
+    for did_document in &[EXAMPLE_DID_DOCUMENT_STR.to_string(), with_public_key.to_string()] {
+        vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, did_document).await?;
+        let report = vcr.verify_vc_detailed("foo", &vc).await;
+        assert!(report.is_valid(), "unexpected error {:?}", report.error());
+        assert_eq!(report.get(CheckType::SignatureVerification), Some(&CheckStatus::Passed));
+        assert_eq!(report.get(CheckType::DocumentEquality), Some(&CheckStatus::Passed));
+        assert_eq!(report.get(CheckType::SignatureRecovery), None);
+
+        let mut manipulated = parsed.clone();
+        manipulated["credentialSubject"] = serde_json::json!({ "id": "did:evan:testcore:0x0" });
+        let report = vcr.verify_vc_detailed("foo", &manipulated.to_string()).await;
+        assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+    }
+
+    // signatures of other keys are rejected
+    let other_pkey = "dfcdcb6d5d09411ae9cbe1b0fd9751ba8803dd4b276d5bf9488ae4ede2669106";
+    let forged = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, other_pkey), &options).await?;
+    let report = vcr.verify_vc_detailed("foo", &forged).await;
+    assert!(matches!(report.get(CheckType::SignatureVerification), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+
+    // other algorithms are rejected instead of being treated as ES256K-R
+    let recoverable = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let mut forged: Value = serde_json::from_str(&recoverable)?;
+    let jws = forged["proof"]["jws"].as_str().unwrap().to_string();
+    let split: Vec<&str> = jws.split('.').collect();
+    for alg in &["none", "HS256", "ES256"] {
+        let header = base64url(format!(r#"{{"alg":"{}","typ":"JWT"}}"#, alg).as_bytes());
+        let forged_jws = format!("{}.{}.{}", header, split[1], split[2]);
+        assert!(matches!(recover_address_and_data(&forged_jws), Err(VadeEvanError::Unsupported(_))));
+        forged["proof"]["jws"] = Value::from(forged_jws);
+        let report = vcr.verify_vc_detailed("foo", &forged.to_string()).await;
+        assert!(matches!(report.get(CheckType::SignatureRecovery), Some(CheckStatus::Failed(VadeEvanError::Unsupported(_)))));
+    }
+
+    options.jws_alg = "ES256".to_string();
+    let result = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &options).await;
+    assert!(matches!(result, Err(VadeEvanError::Unsupported(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_create_and_check_vcs_with_detached_json_web_signatures() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let mut options = CreateVcOptions::new();
+    options.proof_type = "JsonWebSignature2020".to_string();
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &options).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    assert_eq!(parsed["proof"]["type"], "JsonWebSignature2020");
+    // document is not embedded in jws
+    let jws: Vec<&str> = parsed["proof"]["jws"].as_str().unwrap().split('.').collect();
+    assert_eq!(jws[0], "eyJhbGciOiJFUzI1NksiLCJiNjQiOmZhbHNlLCJjcml0IjpbImI2NCJdfQ");
+    assert_eq!(jws[1], "");
+
+    // signatures are verified with addresses and JWKs
+    let mut with_jwk: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR)?;
+    with_jwk["publicKey"][0] = serde_json::json!({
+        "id": veri_method,
+        "type": "JsonWebKey2020",
+        "controller": EXAMPLE_DID,
+        "publicKeyJwk": {
+            "kty": "EC",
+            "crv": "secp256k1",
+            "x": "Wt_VAsC8VfT8uQ7qNjaNfhnFswRapvUd-jaZBG6XUSU",
+            "y": "HSG8a90Gwf8AFPy7-fHYPHFENPKzPXE6r0Z2Dy1T8Q0",
+        },
+    });
+    for did_document in &[EXAMPLE_DID_DOCUMENT_STR.to_string(), with_jwk.to_string()] {
+        vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, did_document).await?;
+        let report = vcr.verify_vc_detailed("foo", &vc).await;
+        assert!(report.is_valid(), "unexpected error {:?}", report.error());
+        assert_eq!(report.get(CheckType::SignatureVerification), Some(&CheckStatus::Passed));
+        assert_eq!(report.get(CheckType::DocumentEquality), None);
+
+        // changes to document and proof options are detected, regardless of property order
+        let mut manipulated = parsed.clone();
+        manipulated["credentialSubject"] = serde_json::json!({ "id": "did:evan:testcore:0x0" });
+        let mut manipulated_proof = parsed.clone();
+        manipulated_proof["proof"]["proofPurpose"] = Value::from("authentication");
+        for vc in &[manipulated, manipulated_proof] {
+            let report = vcr.verify_vc_detailed("foo", &vc.to_string()).await;
+            assert!(!report.is_valid());
+        }
+        let mut reordered = serde_json::Map::new();
+        for (key, value) in parsed.as_object().unwrap().iter().rev() {
+            reordered.insert(key.to_string(), value.clone());
+        }
+        vcr.check_vc("foo", &Value::from(reordered).to_string()).await?;
+    }
+
+    // proofs of other keys are rejected
+    let other_pkey = "dfcdcb6d5d09411ae9cbe1b0fd9751ba8803dd4b276d5bf9488ae4ede2669106";
+    let forged = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, other_pkey), &options).await?;
+    let report = vcr.verify_vc_detailed("foo", &forged).await;
+    assert!(matches!(report.get(CheckType::SignatureVerification), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accepts_jwt_proofs_with_any_claim_and_property_order() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let signer = InMemorySigner::from_hex(veri_method, veri_pkey)?;
+    let vcr = get_vc_resolver_with_did().await?;
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let mut parsed: Value = serde_json::from_str(&vc)?;
+
+    // sign payload as other libraries may, with other claim order and whitespace
+    let mut signed_vc = parsed.clone();
+    signed_vc.as_object_mut().unwrap().remove("proof");
+    let mut reversed_vc = serde_json::Map::new();
+    for (key, value) in signed_vc.as_object().unwrap().iter().rev() {
+        reversed_vc.insert(key.to_string(), value.clone());
+    }
+    let payload = format!(
+        "{{ \"iss\": \"{}\", \"vc\": {}, \"iat\": 1588334400 }}",
+        EXAMPLE_DID,
+        serde_json::to_string_pretty(&Value::from(reversed_vc))?,
+    );
+    let header_and_data = format!(
+        "{}.{}",
+        base64url(r#"{"alg":"ES256K-R","typ":"JWT"}"#.as_bytes()),
+        base64url(payload.as_bytes()),
+    );
+    let signature = sign_with_signer(header_and_data.as_bytes(), &signer).await?;
+    parsed["proof"]["jws"] = Value::from(format!("{}.{}", header_and_data, base64url(&signature)));
+    let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+    assert!(report.is_valid(), "unexpected error {:?}", report.error());
+    assert_eq!(report.get(CheckType::DocumentEquality), Some(&CheckStatus::Passed));
+
+    // payloads without vc claim are rejected
+    let header_and_data = format!(
+        "{}.{}",
+        base64url(r#"{"alg":"ES256K-R","typ":"JWT"}"#.as_bytes()),
+        base64url(format!(r#"{{"iss":"{}"}}"#, EXAMPLE_DID).as_bytes()),
+    );
+    let signature = sign_with_signer(header_and_data.as_bytes(), &signer).await?;
+    parsed["proof"]["jws"] = Value::from(format!("{}.{}", header_and_data, base64url(&signature)));
+    let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+    assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Parse(_)))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_create_and_check_jwt_vcs() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let signer = InMemorySigner::from_hex(veri_method, veri_pkey)?;
+    let clock = FixedClock::new(date("2020-05-01T12:00:00Z"));
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.clock = Box::new(clock.clone());
+    let mut options = CreateVcOptions::new();
+    options.as_jwt = true;
+    options.valid_for = Some(Duration::days(1));
+    let vc_data = r#"{ "id": "foo", "credentialSubject": { "id": "did:evan:testcore:0x0" } }"#;
+    let jwt = vcr.create_vc_with_signer(vc_data, &get_signer(veri_method, veri_pkey), &options).await?;
+    assert_eq!(jwt.split('.').count(), 3);
+    let claims: Value = serde_json::from_slice(&BASE64URL_NOPAD.decode(jwt.split('.').nth(1).unwrap().as_bytes())?)?;
+    assert_eq!(claims["iss"], EXAMPLE_DID);
+    assert_eq!(claims["jti"], "foo");
+    assert_eq!(claims["sub"], "did:evan:testcore:0x0");
+    assert_eq!(claims["nbf"], 1588334400);
+    assert_eq!(claims["exp"], 1588420800);
+    assert_eq!(claims["vc"]["validUntil"], "2020-05-02T12:00:00.000Z");
+    let report = vcr.verify_vc_detailed("foo", &jwt).await;
+    assert!(report.is_valid(), "unexpected error {:?}", report.error());
+    assert_eq!(report.get(CheckType::SignatureRecovery), Some(&CheckStatus::Passed));
+    assert_eq!(report.key_authorization.unwrap().verification_method, veri_method);
+    assert_eq!(vcr.check_vcs(&[("foo", &jwt)]).await.len(), 1);
+    clock.set(date("2020-05-03T12:00:00Z"));
+    assert!(matches!(vcr.check_vc("foo", &jwt).await, Err(VadeEvanError::Validity(_))));
+    clock.set(date("2020-05-01T12:00:00Z"));
+
+    // ES256K signed JWT-VCs are verified with key from DID
+    options.jws_alg = "ES256K".to_string();
+    let jwt = vcr.create_vc_with_signer(vc_data, &get_signer(veri_method, veri_pkey), &options).await?;
+    let report = vcr.verify_vc_detailed("foo", &jwt).await;
+    assert!(report.is_valid(), "unexpected error {:?}", report.error());
+    assert_eq!(report.get(CheckType::SignatureVerification), Some(&CheckStatus::Passed));
+
+    // VC properties may be omitted in favor of registered claims, but must not contradict them
+    let signer = &signer;
+    let sign = |claims: &Value| {
+        let header_and_data = format!(
+            "{}.{}",
+            base64url(format!(r#"{{"alg":"ES256K-R","kid":"{}"}}"#, veri_method).as_bytes()),
+            base64url(claims.to_string().as_bytes()),
+        );
+        async move {
+            let signature = sign_with_signer(header_and_data.as_bytes(), signer).await?;
+            Ok::<_, VadeEvanError>(format!("{}.{}", header_and_data, base64url(&signature)))
+        }
+    };
+    let mut claims = serde_json::json!({
+        "iss": EXAMPLE_DID,
+        "jti": "foo",
+        "sub": "did:evan:testcore:0x0",
+        "nbf": 1588334400,
+        "vc": {
+            "@context": [ "https://www.w3.org/2018/credentials/v1" ],
+            "type": [ "VerifiableCredential" ],
+            "credentialSubject": { "name": "test" },
+        },
+    });
+    vcr.check_vc("foo", &sign(&claims).await?).await?;
+    claims["vc"]["id"] = Value::from("bar");
+    let report = vcr.verify_vc_detailed("foo", &sign(&claims).await?).await;
+    assert!(matches!(report.get(CheckType::Schema), Some(CheckStatus::Failed(VadeEvanError::Schema(_)))));
+
+    // manipulated claims are detected
+    let mut split: Vec<String> = jwt.split('.').map(|part| part.to_string()).collect();
+    let mut manipulated: Value = serde_json::from_slice(&BASE64URL_NOPAD.decode(split[1].as_bytes())?)?;
+    manipulated["exp"] = Value::from(1600000000);
+    manipulated["vc"]["validUntil"] = Value::from("2020-09-13T12:26:40.000Z");
+    split[1] = base64url(manipulated.to_string().as_bytes());
+    assert!(matches!(vcr.check_vc("foo", &split.join(".")).await, Err(VadeEvanError::Signature(_))));
+
+    options.proof_type = "Ed25519Signature2018".to_string();
+    let result = vcr.create_vc_with_ed25519_signer(vc_data, &InMemoryEd25519Signer::generate(veri_method), &options).await;
+    assert!(matches!(result, Err(VadeEvanError::Unsupported(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_create_and_check_vps() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vcr = get_vc_resolver_with_did().await?;
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let mut options = CreateVcOptions::new();
+    options.as_jwt = true;
+    let jwt_vc = vcr.create_vc_with_signer(r#"{ "id": "bar" }"#, &get_signer(veri_method, veri_pkey), &options).await?;
+
+    let vp = vcr.create_vp_with_signer(&[&vc, &jwt_vc], &get_signer(veri_method, veri_pkey), "challenge-1", "example.org").await?;
+    let parsed: Value = serde_json::from_str(&vp)?;
+    assert_eq!(parsed["type"][0], "VerifiablePresentation");
+    assert_eq!(parsed["holder"], EXAMPLE_DID);
+    assert_eq!(parsed["verifiableCredential"][1], Value::from(jwt_vc));
+    assert_eq!(parsed["proof"]["proofPurpose"], "authentication");
+    vcr.check_vp(&vp, "challenge-1", "example.org").await?;
+
+    // challenge and domain have to match
+    for (challenge, domain) in &[("challenge-2", "example.org"), ("challenge-1", "example.com")] {
+        assert!(matches!(vcr.check_vp(&vp, challenge, domain).await, Err(VadeEvanError::Challenge(_))));
+    }
+    let mut other_challenge = parsed.clone();
+    other_challenge["proof"]["challenge"] = Value::from("challenge-2");
+    let result = vcr.check_vp(&other_challenge.to_string(), "challenge-2", "example.org").await;
+    assert!(matches!(result, Err(VadeEvanError::Challenge(_))));
+
+    // VP and VCs have to be valid
+    let mut manipulated = parsed.clone();
+    manipulated["holder"] = Value::from("did:evan:testcore:0x0");
+    assert!(vcr.check_vp(&manipulated.to_string(), "challenge-1", "example.org").await.is_err());
+    let vp = vcr.create_vp_with_signer(&[EXAMPLE_VC_DOCUMENT_MANIPULATED_STR], &get_signer(veri_method, veri_pkey), "challenge-1", "example.org").await?;
+    assert!(matches!(vcr.check_vp(&vp, "challenge-1", "example.org").await, Err(VadeEvanError::Signature(_))));
+    let mut unsigned = parsed.clone();
+    unsigned.as_object_mut().unwrap().remove("proof");
+    assert!(matches!(vcr.check_vp(&unsigned.to_string(), "challenge-1", "example.org").await, Err(VadeEvanError::Signature(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_create_vcs_and_vps_with_signers() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vcr = get_vc_resolver_with_did().await?;
+    let in_memory = InMemorySigner::from_hex(veri_method, veri_pkey)?;
+    let path = std::env::temp_dir().join("vade-evan-vc-resolver-keystore.json");
+    let path = path.to_str().unwrap();
+    KeystoreSigner::save(path, "secret", &in_memory, 10)?;
+    let keystore = KeystoreSigner::open(path, veri_method, "secret")?;
+    std::fs::remove_file(path)?;
+
+    let signers: [&dyn Signer; 2] = [&in_memory, &keystore];
+    for signer in signers.iter() {
+        for proof_type in &["EcdsaPublicKeySecp256k1", "JsonWebSignature2020"] {
+            let mut options = CreateVcOptions::new();
+            options.proof_type = proof_type.to_string();
+            let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, *signer, &options).await?;
+            let parsed: Value = serde_json::from_str(&vc)?;
+            assert_eq!(parsed["issuer"], EXAMPLE_DID);
+            assert_eq!(parsed["proof"]["verificationMethod"], veri_method);
+            vcr.check_vc("foo", &vc).await?;
+        }
+
+        let vp = vcr.create_vp_with_signer(&[EXAMPLE_VC_DOCUMENT_STR], *signer, "challenge-1", "example.org").await?;
+        vcr.check_vp(&vp, "challenge-1", "example.org").await?;
+    }
+
+    // Ed25519 proofs cannot be created with secp256k1 signers
+    let mut options = CreateVcOptions::new();
+    options.proof_type = "Ed25519Signature2018".to_string();
+    let result = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &in_memory, &options).await;
+    assert!(matches!(result, Err(VadeEvanError::Unsupported(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_replayed_challenges() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let clock = FixedClock::new(date("2020-05-01T12:00:00Z"));
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.clock = Box::new(clock.clone());
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let mut options = CreateVcOptions::new();
+    options.challenge = Some("challenge-1".to_string());
+    let with_challenge = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &options).await?;
+
+    // challenges have to be signed
+    let mut parsed: Value = serde_json::from_str(&vc)?;
+    parsed["proof"]["challenge"] = Value::from("challenge-1");
+    let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+    assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+    let mut claims = serde_json::json!({ "iat": 0, "iss": EXAMPLE_DID, "vc": parsed.clone() });
+    claims["vc"].as_object_mut().unwrap().remove("proof");
+    let signer = InMemorySigner::from_hex(veri_method, veri_pkey)?;
+    parsed["proof"]["jws"] = Value::from(create_jwt(&claims, &signer, JWS_ALG_ES256K_R, None).await?);
+    let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+    assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Challenge(_)))));
+    let mut parsed: Value = serde_json::from_str(&with_challenge)?;
+    parsed["proof"]["challenge"] = Value::from("challenge-2");
+    let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+    assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+
+    // challenges are not checked without nonce store
+    let report = vcr.verify_vc_detailed("foo", &with_challenge).await;
+    assert!(matches!(report.get(CheckType::Challenge), Some(CheckStatus::Skipped(_))));
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert_eq!(report.get(CheckType::Challenge), None);
+
+    vcr.nonce_store = Some(Box::new(InMemoryNonceStore::new(Duration::hours(1))));
+    let report = vcr.verify_vc_detailed("foo", &with_challenge).await;
+    assert_eq!(report.get(CheckType::Challenge), Some(&CheckStatus::Passed));
+    let report = vcr.verify_vc_detailed("foo", &with_challenge).await;
+    assert!(matches!(report.get(CheckType::Challenge), Some(CheckStatus::Failed(VadeEvanError::Challenge(_)))));
+
+    // VPs can only be checked once per challenge
+    let vp = vcr.create_vp_with_signer(&[&vc], &get_signer(veri_method, veri_pkey), "challenge-2", "example.org").await?;
+    vcr.check_vp(&vp, "challenge-2", "example.org").await?;
+    assert!(matches!(vcr.check_vp(&vp, "challenge-2", "example.org").await, Err(VadeEvanError::Challenge(_))));
+
+    // proofs are rejected once their challenges could have been forgotten
+    let vp = vcr.create_vp_with_signer(&[&vc], &get_signer(veri_method, veri_pkey), "challenge-3", "example.org").await?;
+    clock.advance(Duration::minutes(59));
+    assert!(matches!(vcr.check_vp(&vp, "challenge-3", "example.org").await, Err(VadeEvanError::Challenge(_))));
+    clock.advance(Duration::hours(2));
+    assert!(matches!(vcr.check_vp(&vp, "challenge-2", "example.org").await, Err(VadeEvanError::Challenge(_))));
+    let report = vcr.verify_vc_detailed("foo", &with_challenge).await;
+    assert!(matches!(report.get(CheckType::Challenge), Some(CheckStatus::Failed(VadeEvanError::Challenge(_)))));
+
+    // proofs from the future are rejected
+    let vp = vcr.create_vp_with_signer(&[&vc], &get_signer(veri_method, veri_pkey), "challenge-4", "example.org").await?;
+    clock.advance(-Duration::minutes(6));
+    assert!(matches!(vcr.check_vp(&vp, "challenge-4", "example.org").await, Err(VadeEvanError::Challenge(_))));
+    clock.advance(Duration::minutes(1));
+    vcr.check_vp(&vp, "challenge-4", "example.org").await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_use_keys_of_controllers() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let issuer = "did:evan:testcore:0x1000000000000000000000000000000000000001";
+    let intermediate = "did:evan:testcore:0x1000000000000000000000000000000000000002";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    set_did_document(&mut vcr, issuer, Some(intermediate), &[]).await?;
+    set_did_document(&mut vcr, intermediate, Some(EXAMPLE_DID), &[]).await?;
+
+    let vc = vcr.create_vc_with_signer(&format!(r#"{{ "id": "foo", "issuer": "{}" }}"#, issuer), &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid());
+    assert_eq!(report.key_authorization, Some(KeyAuthorization {
+        verification_method: veri_method.to_string(),
+        controller_path: vec![issuer.to_string(), intermediate.to_string(), EXAMPLE_DID.to_string()],
+        relationship: Some("authentication".to_string()),
+    }));
+
+    // keys of other DIDs can be listed in the issuer's verification relationships
+    set_did_document(&mut vcr, issuer, None, &[veri_method]).await?;
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid());
+    assert_eq!(report.key_authorization.unwrap().controller_path, vec![issuer.to_string()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn honors_verification_relationships_for_proof_purpose() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let mut did_document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR)?;
+    did_document["assertionMethod"] = serde_json::json!([ format!("{}#key-2", EXAMPLE_DID) ]);
+    vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+
+    // key-1 is only listed in authentication, so it cannot be used for assertions
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    match vcr.check_vc("foo", &vc).await {
+        Ok(_) => panic!("VC signed with key not authorized for assertions recognized as valid"),
+        Err(e) => assert_eq!(e.to_string(), format!(
+            "key {} is not authorized for assertionMethod by issuer {}", veri_method, EXAMPLE_DID)),
+    }
+
+    // proofPurpose is signed
+    let mut parsed: Value = serde_json::from_str(&vc)?;
+    parsed["proof"]["proofPurpose"] = Value::from("authentication");
+    let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+    assert!(matches!(report.get(CheckType::DocumentEquality), Some(CheckStatus::Failed(VadeEvanError::Signature(_)))));
+
+    // keys are looked up in assertionMethod, whatever proofPurpose is claimed
+    let signer = InMemorySigner::from_hex(veri_method, veri_pkey)?;
+    for (proof_purpose, expected) in &[("authentication", "KeyNotFound"), ("foo", "Signature")] {
+        parsed["proof"]["proofPurpose"] = Value::from(*proof_purpose);
+        let mut claims = serde_json::json!({ "iat": 0, "iss": EXAMPLE_DID, "proof": parsed["proof"] });
+        claims["proof"].as_object_mut().unwrap().remove("jws");
+        claims["vc"] = parsed.clone();
+        claims["vc"].as_object_mut().unwrap().remove("proof");
+        parsed["proof"]["jws"] = Value::from(create_jwt(&claims, &signer, JWS_ALG_ES256K_R, None).await?);
+        let report = vcr.verify_vc_detailed("foo", &parsed.to_string()).await;
+        assert_eq!(report.get(CheckType::DocumentEquality), Some(&CheckStatus::Passed));
+        match report.get(CheckType::KeyLookup) {
+            Some(CheckStatus::Failed(VadeEvanError::KeyNotFound(_))) => assert_eq!(*expected, "KeyNotFound"),
+            Some(CheckStatus::Failed(VadeEvanError::Signature(_))) => assert_eq!(*expected, "Signature"),
+            status => panic!("unexpected key lookup result {:?}", status),
+        }
+    }
+
+    // keys in publicKey are only authorized if the DID document declares no relationships at all
+    did_document.as_object_mut().unwrap().remove("assertionMethod");
+    did_document.as_object_mut().unwrap().remove("authentication");
+    did_document["keyAgreement"] = serde_json::json!([ format!("{}#key-2", EXAMPLE_DID) ]);
+    vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+    assert!(matches!(vcr.check_vc("foo", &vc).await, Err(VadeEvanError::KeyNotFound(_))));
+    did_document.as_object_mut().unwrap().remove("keyAgreement");
+    vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid());
+    assert_eq!(report.key_authorization.unwrap().relationship, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resolves_relative_key_references() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let mut did_document: Value = serde_json::from_str(EXAMPLE_DID_DOCUMENT_STR)?;
+    did_document["publicKey"][0]["id"] = Value::from("#key-1");
+    did_document["authentication"] = serde_json::json!([ "#key-1" ]);
+    vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid(), "unexpected error {:?}", report.error());
+    assert_eq!(report.key_authorization.unwrap().relationship, Some("authentication".to_string()));
+
+    // embedded keys may use relative ids as well
+    did_document["assertionMethod"] = serde_json::json!([ did_document["publicKey"][0].clone() ]);
+    vcr.vade.as_mut().unwrap().set_did_document(EXAMPLE_DID, &did_document.to_string()).await?;
+    let report = vcr.verify_vc_detailed("foo", &vc).await;
+    assert!(report.is_valid(), "unexpected error {:?}", report.error());
+    assert_eq!(report.key_authorization.unwrap().relationship, Some("assertionMethod".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stops_following_controllers_on_loops_and_deep_chains() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let dids: Vec<String> = (1..=MAX_CONTROLLER_DEPTH + 1)
+        .map(|i| format!("did:evan:testcore:0x{:040x}", i))
+        .collect();
+    let mut vcr = get_vc_resolver_with_did().await?;
+
+    // DIDs controlling each other
+    set_did_document(&mut vcr, &dids[0], Some(&dids[1]), &[]).await?;
+    set_did_document(&mut vcr, &dids[1], Some(&dids[0]), &[]).await?;
+    let vc = vcr.create_vc_with_signer(&format!(r#"{{ "id": "foo", "issuer": "{}" }}"#, dids[0]), &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    match vcr.check_vc("foo", &vc).await {
+        Ok(_) => panic!("VC with controller loop recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Signature(_))),
+    }
+
+    // chain of controllers longer than allowed
+    for (i, did) in dids.iter().enumerate() {
+        set_did_document(&mut vcr, did, Some(dids.get(i + 1).map(|did| did.as_str()).unwrap_or(EXAMPLE_DID)), &[]).await?;
+    }
+    match vcr.check_vc("foo", &vc).await {
+        Ok(_) => panic!("VC with too many controllers recognized as valid"),
+        Err(e) => assert!(e.to_string().ends_with(&format!("stopped after {} controllers", MAX_CONTROLLER_DEPTH))),
+    }
+    set_did_document(&mut vcr, &dids[1], Some(EXAMPLE_DID), &[]).await?;
+    vcr.check_vc("foo", &vc).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_restrict_issuers_and_proof_types_with_policy() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vcr = get_vc_resolver_with_did().await?;
+
+    vcr.policy.trusted_issuers = Some(TrustedIssuerRegistry::new().with_issuer("VerifiableCredential", EXAMPLE_DID));
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await;
+    assert_eq!(report.get(CheckType::TrustedIssuer), Some(&CheckStatus::Passed));
+
+    vcr.policy.trusted_issuers = Some(TrustedIssuerRegistry::new()
+        .with_issuer("VerifiableCredential", "did:evan:testcore:0x67ced07dd4f37aa2319bedd97d040b64888c57bc"));
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await;
+    assert!(matches!(report.get(CheckType::TrustedIssuer), Some(CheckStatus::Failed(VadeEvanError::Policy(_)))));
+    assert_eq!(report.get(CheckType::SignatureRecovery), None);
+
+    vcr.policy = VerificationPolicy::new();
+    vcr.policy.allowed_proof_types = vec!["Ed25519Signature2018".to_string()];
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("VC with disallowed proof type recognized as valid"),
+        Err(e) => assert_eq!(e.to_string(), "proof type \"EcdsaPublicKeySecp256k1\" is not allowed"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_trust_issuers_per_credential_type() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let supplier_vc = vcr.create_vc_with_signer(
+        &format!(r#"{{ "id": "foo", "issuer": "{}", "type": [ "VerifiableCredential", "SupplierCredential" ] }}"#, EXAMPLE_DID),
+        &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let auditor_vc = vcr.create_vc_with_signer(
+        &format!(r#"{{ "id": "bar", "issuer": "{}", "type": [ "VerifiableCredential", "AuditorCredential" ] }}"#, EXAMPLE_DID),
+        &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+
+    vcr.policy.trusted_issuers = Some(TrustedIssuerRegistry::from_json(&format!(
+        r#"{{ "SupplierCredential": [ "{}" ] }}"#, EXAMPLE_DID))?);
+    vcr.check_vc("foo", &supplier_vc).await?;
+    match vcr.check_vc("bar", &auditor_vc).await {
+        Ok(_) => panic!("VC of untrusted issuer recognized as valid"),
+        Err(e) => assert_eq!(e.to_string(), format!(
+            "issuer \"{}\" is not trusted to issue VCs of type \"VerifiableCredential\", \"AuditorCredential\"", EXAMPLE_DID)),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_require_checks_and_status_types_with_policy() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    let without_status = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    let with_other_status = vcr.create_vc_with_signer(
+        r#"{ "id": "bar", "credentialStatus": { "id": "https://example.org/status/bar", "type": "RevocationList2020Status" } }"#,
+        &get_signer(veri_method, veri_pkey), &CreateVcOptions::new()).await?;
+    vcr.check_vc("foo", &without_status).await?;
+    let report = vcr.verify_vc_detailed("bar", &with_other_status).await;
+    assert!(matches!(report.get(CheckType::CredentialStatus), Some(CheckStatus::Skipped(_))));
+
+    // skipped checks fail if required
+    vcr.policy.required_checks = vec![CheckType::CredentialStatus];
+    let report = vcr.verify_vc_detailed("foo", &without_status).await;
+    assert!(matches!(report.get(CheckType::CredentialStatus), Some(CheckStatus::Failed(VadeEvanError::Policy(_)))));
+    assert!(vcr.check_vc("bar", &with_other_status).await.is_err());
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+
+    // disallowed status types fail
+    vcr.policy = VerificationPolicy::new();
+    vcr.policy.allowed_status_types = Some(vec!["evan:evanCredential".to_string()]);
+    vcr.check_vc("foo", &without_status).await?;
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    match vcr.check_vc("bar", &with_other_status).await {
+        Ok(_) => panic!("VC with disallowed status type recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Policy(_))),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn does_not_fetch_statuses_and_dids_in_offline_mode() -> Result<(), Box<dyn std::error::Error>> {
+    let transport = get_fixture_transport();
+    let status_cache = StatusCache::new(StatusCacheMode::Cached, Duration::zero());
+    let did_cache = LruCache::new(100, Duration::hours(1));
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.transport = Box::new(transport.clone());
+    vcr.status_cache = Some(status_cache.clone());
+    vcr.did_cache = Some(did_cache.clone());
+    vcr.policy.offline = true;
+
+    // DIDs not cached are not resolved
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("VC document with uncached DID recognized as valid"),
+        Err(e) => assert_eq!(format!("{}", e), format!("offline, DID {} is not cached", EXAMPLE_DID)),
+    }
+    match vcr.check_vcs(&[(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR)]).await.remove(0) {
+        Ok(_) => panic!("VC document with uncached DID recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Network(_))),
+    }
+    assert!(did_cache.is_empty());
+
+    // DIDs resolved online are cached for offline usage
+    vcr.policy.offline = false;
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(did_cache.len(), 1);
+    assert_eq!(transport.requests().len(), 1);
+    status_cache.clear();
+    vcr.vade = None;
+    vcr.policy.offline = true;
+
+    // unknown statuses are skipped
+    let report = vcr.verify_vc_detailed(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await;
+    assert!(report.is_valid());
+    assert!(matches!(report.get(CheckType::CredentialStatus), Some(CheckStatus::Skipped(_))));
+    assert!(vcr.check_vcs(&[(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR)]).await[0].is_ok());
+    assert_eq!(transport.requests().len(), 1);
+
+    // last known statuses are used, even if outdated
+    status_cache.set(
+        &format!("https://testcore.evan.network/vc/status/{}", EXAMPLE_VC_NAME), false, &Utc::now());
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("revoked VC document recognized as valid"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Revoked(_))),
+    }
+    status_cache.set(
+        &format!("https://testcore.evan.network/vc/status/{}", EXAMPLE_VC_NAME), true, &Utc::now());
+    let warnings = vcr.check_vc_with_warnings(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("offline, using status from "));
+    assert_eq!(transport.requests().len(), 1);
+
+    // statuses are required when checking online
+    vcr.policy.offline = false;
+    vcr.policy.required_checks = vec![CheckType::CredentialStatus];
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(transport.requests().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_check_validity_periods() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let clock = FixedClock::new(date("2020-05-01T12:00:00Z"));
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.clock = Box::new(clock.clone());
+    vcr.clock_skew = Duration::minutes(1);
+
+    let mut options = CreateVcOptions::new();
+    options.valid_for = Some(Duration::days(1));
+    let vc = vcr.create_vc_with_signer(r#"{ "id": "foo" }"#, &get_signer(veri_method, veri_pkey), &options).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    assert_eq!(parsed["validFrom"], "2020-05-01T12:00:00.000Z");
+    assert_eq!(parsed["validUntil"], "2020-05-02T12:00:00.000Z");
+    vcr.check_vc("foo", &vc).await?;
+
+    // clock skew is tolerated
+    clock.set(date("2020-05-01T11:59:30Z"));
+    vcr.check_vc("foo", &vc).await?;
+    clock.set(date("2020-05-02T12:00:30Z"));
+    vcr.check_vc("foo", &vc).await?;
+
+    // not yet valid and expired VCs are rejected
+    for now in &[date("2020-05-01T11:58:00Z"), date("2020-05-02T12:02:00Z")] {
+        clock.set(*now);
+        let report = vcr.verify_vc_detailed("foo", &vc).await;
+        assert!(matches!(report.get(CheckType::Expiry), Some(CheckStatus::Failed(VadeEvanError::Validity(_)))));
+        assert_eq!(report.get(CheckType::SignatureRecovery), None);
+    }
+
+    // expirationDate is checked as well, also for VCs without proof
+    clock.set(date("2020-05-01T12:00:00Z"));
+    let unsigned = r#"{ "id": "foo", "expirationDate": "2020-04-01T00:00:00Z" }"#;
+    assert!(matches!(vcr.check_vc("foo", unsigned).await, Err(VadeEvanError::Validity(_))));
+    let unsigned = r#"{ "id": "foo", "expirationDate": "yesterday" }"#;
+    assert!(matches!(vcr.check_vc("foo", unsigned).await, Err(VadeEvanError::Schema(_))));
+    let report = vcr.verify_vc_detailed("foo", r#"{ "id": "foo" }"#).await;
+    assert!(matches!(report.get(CheckType::Expiry), Some(CheckStatus::Skipped(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_cache_vc_statuses() -> Result<(), Box<dyn std::error::Error>> {
+    for (mode, expected_requests) in &[(StatusCacheMode::Cached, 1), (StatusCacheMode::Strict, 2)] {
+        let transport = get_fixture_transport();
+        let mut vcr = get_vc_resolver_with_did().await?;
+        vcr.transport = Box::new(transport.clone());
+        vcr.status_cache = Some(StatusCache::new(*mode, Duration::minutes(5)));
+
+        vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+        vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+        assert_eq!(transport.requests().len(), *expected_requests);
+    }
+
+    // cached statuses expire according to resolver clock
+    let clock = FixedClock::new(date("2020-05-01T12:00:00Z"));
+    let transport = get_fixture_transport();
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.clock = Box::new(clock.clone());
+    vcr.transport = Box::new(transport.clone());
+    vcr.status_cache = Some(StatusCache::new(StatusCacheMode::Cached, Duration::minutes(5)));
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    clock.advance(Duration::minutes(4));
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(transport.requests().len(), 1);
+    clock.advance(Duration::minutes(2));
+    vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(transport.requests().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_use_last_known_vc_status_in_degraded_mode() -> Result<(), Box<dyn std::error::Error>> {
+    // fetch status once, so it is known to cache
+    let status_cache = StatusCache::new(StatusCacheMode::Degraded, Duration::zero());
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.status_cache = Some(status_cache.clone());
+    let warnings = vcr.check_vc_with_warnings(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert!(warnings.is_empty());
+
+    // status endpoint is unreachable now, status is outdated but still used
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.transport = Box::new(FixtureTransport::new());
+    vcr.status_cache = Some(status_cache.clone());
+    let warnings = vcr.check_vc_with_warnings(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with(&format!(
+        "could not fetch status \"https://testcore.evan.network/vc/status/{}\"", EXAMPLE_VC_NAME)));
+
+    // invalid responses, e.g. error pages of proxies, are handled like unreachable endpoints
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.transport = Box::new(FixtureTransport::new().with_response(
+        "GET",
+        &format!("https://testcore.evan.network/vc/status/{}", EXAMPLE_VC_NAME),
+        "<html>502 Bad Gateway</html>",
+    ));
+    vcr.status_cache = Some(status_cache.clone());
+    let warnings = vcr.check_vc_with_warnings(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await?;
+    assert_eq!(warnings.len(), 1);
+
+    // outdated statuses do not count as hits
+    assert_eq!(status_cache.stats().hits, 0);
+    assert_eq!(status_cache.stats().misses, 3);
+
+    // other modes fail if status cannot be fetched
+    let mut cached = status_cache.clone();
+    cached.mode = StatusCacheMode::Cached;
+    vcr.status_cache = Some(cached);
+    match vcr.check_vc_with_warnings(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("vc checked without status"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Parse(_))),
+    }
+
+    // unknown statuses cannot be used
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.transport = Box::new(FixtureTransport::new());
+    vcr.status_cache = Some(StatusCache::new(StatusCacheMode::Degraded, Duration::zero()));
+    assert!(vcr.check_vc_with_warnings(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_check_multiple_vcs_at_once() -> Result<(), Box<dyn std::error::Error>> {
+    // resolve DIDs and statuses from the same fixtures, so lookups can be counted
+    let did_url = format!("https://testcore.evan.network/did/{}", EXAMPLE_DID);
+    let transport = get_fixture_transport()
+        .with_response("GET", &did_url, &format!(r#"{{"status":"success","did":{}}}"#, EXAMPLE_DID_DOCUMENT_STR));
+    let mut vcr_vade = Vade::new();
+    vcr_vade.register_did_resolver(Box::from(
+        RustDidResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(transport.clone()))));
+    let mut vcr = RustVcResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(transport.clone()));
+    vcr.vade = Some(Box::from(vcr_vade));
+    vcr.batch_parallelism = 2;
+
+    let results = vcr.check_vcs(&[
+        (EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR),
+        (EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_MANIPULATED_STR),
+        ("qwer", "qwer"),
+        (EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR),
+    ]).await;
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0], Ok(()));
+    assert!(matches!(results[1], Err(VadeEvanError::Signature(_))));
+    assert!(matches!(results[2], Err(VadeEvanError::Parse(_))));
+    assert_eq!(results[3], Ok(()));
+    let requests = transport.requests();
+    assert_eq!(requests.iter().filter(|r| r.url == did_url).count(), 1);
+    assert_eq!(requests.len(), 2);
+
+    // failed lookups are reported for each affected VC
+    let mut vcr_vade = Vade::new();
+    vcr_vade.register_did_resolver(Box::from(
+        RustDidResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(FixtureTransport::new()))));
+    let mut vcr = RustVcResolverEvan::new_with_transport(ResolverConfig::new(), Box::new(FixtureTransport::new()));
+    vcr.vade = Some(Box::from(vcr_vade));
+    let results = vcr.check_vcs(&[
+        (EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR),
+        (EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR),
+    ]).await;
+    assert!(results.iter().all(|result| matches!(result, Err(VadeEvanError::Network(_)))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vc_resolver_cannot_create_vcs_without_id_and_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vcr = RustVcResolverEvan::new();
+
+    match vcr.create_vc_with_signer(r#"{ "credentialSubject": { "foo": "bar" } }"#, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await {
+        Ok(_) => panic!("created vc without id"),
+        Err(e) => assert_eq!(format!("{}", e), "\"id\" is required for offline VCs"),
+    };
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vc_resolver_can_create_set_and_get_vcs_with_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.vc_storage = Some(Box::from(InMemoryVcStorage::new()));
+
+    let vc = vcr.create_vc_with_signer(r#"{ "credentialSubject": { "foo": "bar" } }"#, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    let vc_id = parsed["id"].as_str().unwrap();
+    assert!(vc_id.starts_with("vc:evan:testcore:0x"));
+    assert_eq!(vc_id.len(), "vc:evan:testcore:0x".len() + 64);
+
+    // other VCs get other ids
+    let other_vc = vcr.create_vc_with_signer(r#"{ "credentialSubject": { "foo": "bar" } }"#, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await?;
+    let other_parsed: Value = serde_json::from_str(&other_vc)?;
+    assert!(other_parsed["id"] != parsed["id"]);
+
+    let mut vade = Vade::new();
+    vade.register_vc_resolver(Box::from(vcr));
+    vade.set_vc_document(vc_id, &vc).await?;
+    let fetched = vade.get_vc_document(vc_id).await?;
+    assert_eq!(fetched, vc);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vc_resolver_cannot_set_invalid_vcs() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.vc_storage = Some(Box::from(InMemoryVcStorage::new()));
+
+    match vcr.set_vc_document(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_MANIPULATED_STR).await {
+        Ok(_) => panic!("manipulated VC document has been stored"),
+        Err(_) => (),
+    };
+    match vcr.set_vc_document("other-id", EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(_) => panic!("VC document has been stored under wrong id"),
+        Err(e) => assert!(format!("{}", e).contains("does not match")),
+    };
+    assert!(vcr.get_vc_document(EXAMPLE_VC_NAME).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn vc_resolver_can_use_evan_vc_registry() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vc_id = "vc:evan:testcore:0x1234567890123456789012345678901234567890123456789012345678901234";
+    let mut routes = HashMap::new();
+    routes.insert(format!("POST /vc/{}", vc_id), r#"{"status":"success"}"#.to_string());
+    let server = MockServer::start(routes);
+    let config = ResolverConfig::new().with_endpoint("testcore", &server.url);
+
+    let mut vcr = get_vc_resolver_with_did().await?;
+    vcr.vc_storage = Some(Box::from(EvanVcStorage::new(config)));
+
+    // registry does not create ids
+    let result = vcr.create_vc_with_signer(r#"{ "credentialSubject": { "foo": "bar" } }"#, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await;
+    assert!(matches!(result, Err(VadeEvanError::Unsupported(_))));
+
+    let vc_data = format!(r#"{{ "id": "{}", "credentialSubject": {{ "foo": "bar" }} }}"#, vc_id);
+    let vc = vcr.create_vc_with_signer(&vc_data, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    vcr.set_vc_document(vc_id, &vc).await?;
+
+    let requests = server.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let set_request: Value = serde_json::from_str(&requests[0].body)?;
+    assert_eq!(set_request["vc"], parsed);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_vc_returns_errors_for_malformed_documents() -> Result<(), Box<dyn std::error::Error>> {
+    let vcr = get_vc_resolver_with_did().await?;
+    let valid: Value = serde_json::from_str(EXAMPLE_VC_DOCUMENT_STR)?;
+    let jws = valid["proof"]["jws"].as_str().unwrap();
+    let jws_parts: Vec<&str> = jws.split('.').collect();
+
+    type IsExpectedError = fn(&VadeEvanError) -> bool;
+    let mut cases: Vec<(String, IsExpectedError)> = Vec::new();
+    cases.push(("qwer".to_string(), |e| matches!(e, VadeEvanError::Parse(_))));
+    cases.push(("[]".to_string(), |e| matches!(e, VadeEvanError::Schema(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["jws"] = Value::Null;
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::Schema(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["jws"] = Value::from("abc");
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::Parse(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["jws"] = Value::from(format!("{}.!!!.{}", jws_parts[0], jws_parts[2]));
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::Parse(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["jws"] = Value::from(format!("{}.{}.AAAA", jws_parts[0], jws_parts[1]));
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::Signature(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["jws"] = Value::from(format!("{}.e30.{}", jws_parts[0], jws_parts[2]));
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::Parse(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["verificationMethod"] = Value::from(42);
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::Schema(_))));
+    let mut vc = valid.clone();
+    vc["proof"]["verificationMethod"] = Value::from(format!("{}#key-2", EXAMPLE_DID));
+    cases.push((vc.to_string(), |e| matches!(e, VadeEvanError::KeyNotFound(_))));
+    let vc = vcr.create_vc_with_signer(
+        r#"{ "id": "foo" }"#,
+        &get_signer("did:evan:testcore:0x1234#key-1", "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a"), &CreateVcOptions::new()).await?;
+    cases.push((vc, |e| matches!(e, VadeEvanError::Network(_))));
+
+    for (document, is_expected_error) in cases {
+        match vcr.check_vc(EXAMPLE_VC_NAME, &document).await {
+            Ok(()) => panic!("malformed document accepted: {}", document),
+            Err(e) => assert!(is_expected_error(&e), "unexpected error {:?} for {}", e, document),
+        }
+    }
+
+    // resolver without vade cannot resolve DIDs
+    let vcr = RustVcResolverEvan::new();
+    match vcr.check_vc(EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_STR).await {
+        Ok(()) => panic!("vc checked without DID resolver"),
+        Err(e) => assert!(matches!(e, VadeEvanError::Config(_))),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_vc_returns_errors_for_malformed_input() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vcr = RustVcResolverEvan::new();
+
+    let result = vcr.create_vc_with_signer("qwer", &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await;
+    assert!(matches!(result, Err(VadeEvanError::Parse(_))));
+    let result = vcr.create_vc_with_signer(r#"{ "id": "foo", "@context": "foo" }"#, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await;
+    assert!(matches!(result, Err(VadeEvanError::Schema(_))));
+    let result = vcr.create_vc_with_signer(r#"{ "id": "foo", "issuer": 42 }"#, &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await;
+    assert!(matches!(result, Err(VadeEvanError::Schema(_))));
+    let result = InMemorySigner::from_hex(&veri_method, "not a key");
+    assert!(matches!(result, Err(VadeEvanError::Signature(_))));
+
+    // issuer may be given as object
+    let vc = vcr.create_vc_with_signer(
+        &format!(r#"{{ "id": "foo", "issuer": {{ "id": "{}" }} }}"#, EXAMPLE_DID), &get_signer(&veri_method, &veri_pkey), &CreateVcOptions::new()).await?;
+    let parsed: Value = serde_json::from_str(&vc)?;
+    assert!(parsed["proof"].is_object());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn can_still_create_vcs_and_vps_with_hex_keys() -> Result<(), Box<dyn std::error::Error>> {
+    let veri_method = "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1";
+    let veri_pkey = "01734663843202e2245e5796cb120510506343c67915eb4f9348ac0d8c2cf22a";
+    let vcr = get_vc_resolver_with_did().await?;
+
+    let vc = vcr.create_vc(r#"{ "id": "foo" }"#, veri_method, veri_pkey).await?;
+    vcr.check_vc("foo", &vc).await?;
+    let mut options = CreateVcOptions::new();
+    options.jws_alg = "ES256K".to_string();
+    let vc = vcr.create_vc_with_options(r#"{ "id": "foo" }"#, veri_method, veri_pkey, &options).await?;
+    vcr.check_vc("foo", &vc).await?;
+    let vp = vcr.create_vp(&[&vc], veri_method, veri_pkey, "challenge-1", "example.org").await?;
+    vcr.check_vp(&vp, "challenge-1", "example.org").await?;
+
+    // hex keys are used as Ed25519 seeds for Ed25519 proofs
+    options.proof_type = "Ed25519Signature2018".to_string();
+    let vc = vcr.create_vc_with_options(r#"{ "id": "foo" }"#, veri_method, veri_pkey, &options).await?;
+    let signer = InMemoryEd25519Signer::from_hex(veri_method, veri_pkey)?;
+    let mut expected: Value = serde_json::from_str(
+        &vcr.create_vc_with_ed25519_signer(r#"{ "id": "foo" }"#, &signer, &options).await?)?;
+    let mut parsed: Value = serde_json::from_str(&vc)?;
+    expected["validFrom"] = Value::Null;
+    parsed["validFrom"] = Value::Null;
+    expected["proof"]["created"] = Value::Null;
+    parsed["proof"]["created"] = Value::Null;
+    expected["proof"]["jws"] = Value::Null;
+    parsed["proof"]["jws"] = Value::Null;
+    assert_eq!(parsed, expected);
+
+    let result = vcr.create_vc(r#"{ "id": "foo" }"#, veri_method, "not a key").await;
+    assert!(matches!(result, Err(VadeEvanError::Signature(_))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn errors_from_trait_implementations_can_be_downcasted() -> Result<(), Box<dyn std::error::Error>> {
+    let vcr = get_vc_resolver_with_did().await?;
+
+    match VcResolver::check_vc(&vcr, EXAMPLE_VC_NAME, EXAMPLE_VC_DOCUMENT_MANIPULATED_STR).await {
+        Ok(()) => panic!("manipulated VC document recocnized as valid"),
+        Err(e) => assert!(matches!(e.downcast_ref::<VadeEvanError>(), Some(VadeEvanError::Signature(_)))),
+    }
+
+    Ok(())
+}
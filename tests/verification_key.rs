@@ -0,0 +1,104 @@
+/*
+  Copyright (c) 2018-present evan GmbH.
+
+  Licensed under the Apache License, Version 2.0 (the "License");
+  you may not use this file except in compliance with the License.
+  You may obtain a copy of the License at
+
+      http://www.apache.org/licenses/LICENSE-2.0
+
+  Unless required by applicable law or agreed to in writing, software
+  distributed under the License is distributed on an "AS IS" BASIS,
+  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+  See the License for the specific language governing permissions and
+  limitations under the License.
+*/
+
+extern crate vade_evan;
+
+use serde_json::{ json, Value };
+use vade_evan::utils::errors::VadeEvanError;
+use vade_evan::utils::verification_key::VerificationKey;
+
+// key of identity with address 0x001de828935e8c7e4cb56fe610495cae63fb2612 in different formats
+const ADDRESS: &str = "0x001de828935e8c7e4cb56fe610495cae63fb2612";
+const PUBLIC_KEY_HEX: &str = "045adfd502c0bc55f4fcb90eea36368d7e19c5b3045aa6f51dfa3699046e9751251d21bc6bdd06c1ff0014fcbbf9f1d83c714434f2b33d713aaf46760f2d53f10d";
+const PUBLIC_KEY_HEX_COMPRESSED: &str = "035adfd502c0bc55f4fcb90eea36368d7e19c5b3045aa6f51dfa3699046e975125";
+const PUBLIC_KEY_BASE58: &str = "zogmFPqKrYgNogxk1XKMsoG45JtPDw3oynNS7eM583SU";
+const PUBLIC_KEY_JWK_X: &str = "Wt_VAsC8VfT8uQ7qNjaNfhnFswRapvUd-jaZBG6XUSU";
+const PUBLIC_KEY_JWK_Y: &str = "HSG8a90Gwf8AFPy7-fHYPHFENPKzPXE6r0Z2Dy1T8Q0";
+
+fn method(property: &str, value: Value) -> Value {
+    let mut method = json!({
+        "id": "did:evan:testcore:0x0ef0e584c714564a4fc0c6c367edccb0c1cbf65f#key-1",
+        "type": "Secp256k1VerificationKey2018",
+    });
+    method[property] = value;
+    method
+}
+
+#[test]
+fn can_derive_addresses_from_all_key_formats() -> Result<(), VadeEvanError> {
+    let methods = vec![
+        method("ethereumAddress", Value::from(ADDRESS.to_uppercase().replace("0X", "0x"))),
+        method("publicKeyHex", Value::from(PUBLIC_KEY_HEX)),
+        method("publicKeyHex", Value::from(format!("0x{}", PUBLIC_KEY_HEX_COMPRESSED))),
+        method("publicKeyBase58", Value::from(PUBLIC_KEY_BASE58)),
+        method("publicKeyJwk", json!({ "kty": "EC", "crv": "secp256k1", "x": PUBLIC_KEY_JWK_X, "y": PUBLIC_KEY_JWK_Y })),
+    ];
+    for method in methods {
+        assert_eq!(VerificationKey::parse(&method)?.ethereum_address()?, ADDRESS);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn can_parse_ed25519_keys() -> Result<(), VadeEvanError> {
+    // public key from RFC 8032, test 1
+    let public_key = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    let ed25519 = |property: &str, value: Value| {
+        let mut method = method(property, value);
+        method["type"] = Value::from("Ed25519VerificationKey2018");
+        method
+    };
+    let methods = vec![
+        ed25519("publicKeyHex", Value::from(public_key)),
+        ed25519("publicKeyBase58", Value::from("FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z")),
+        method("publicKeyJwk", json!({ "kty": "OKP", "crv": "Ed25519", "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo" })),
+    ];
+    for method in methods {
+        match VerificationKey::parse(&method)? {
+            VerificationKey::Ed25519(key) => assert_eq!(hex::encode(key.as_bytes()), public_key),
+            key => panic!("unexpected key {:?}", key),
+        }
+    }
+    let key = VerificationKey::parse(&ed25519("publicKeyHex", Value::from(public_key)))?;
+    assert!(matches!(key.ethereum_address(), Err(VadeEvanError::Signature(_))));
+    let too_short = ed25519("publicKeyHex", Value::from(&public_key[2..]));
+    assert!(matches!(VerificationKey::parse(&too_short), Err(VadeEvanError::Schema(_))));
+
+    Ok(())
+}
+
+#[test]
+fn returns_errors_for_invalid_keys() {
+    let cases = vec![
+        method("publicKeyHex", Value::from("xyz")),
+        method("publicKeyHex", Value::from("02b97c30")),
+        method("publicKeyHex", Value::from(format!("05{}", &PUBLIC_KEY_HEX[2..]))),
+        method("publicKeyBase58", Value::from("0OIl")),
+        method("publicKeyJwk", json!({ "kty": "EC", "crv": "secp256k1", "x": PUBLIC_KEY_JWK_X })),
+        method("publicKeyJwk", json!({ "kty": "EC", "crv": "secp256k1", "x": PUBLIC_KEY_JWK_X, "y": "AAAA" })),
+        method("type", Value::from("Secp256k1VerificationKey2018")),
+    ];
+    for method in cases {
+        match VerificationKey::parse(&method) {
+            Ok(key) => panic!("invalid key parsed: {:?}", key),
+            Err(e) => assert!(matches!(e, VadeEvanError::Schema(_)), "unexpected error {:?}", e),
+        }
+    }
+
+    let p256 = method("publicKeyJwk", json!({ "kty": "EC", "crv": "P-256", "x": PUBLIC_KEY_JWK_X, "y": PUBLIC_KEY_JWK_Y }));
+    assert!(matches!(VerificationKey::parse(&p256), Err(VadeEvanError::Unsupported(_))));
+}